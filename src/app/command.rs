@@ -0,0 +1,304 @@
+// Commands
+//
+// This file implements the command mode (commands typed after `:`).
+
+use std::fs;
+
+use crate::app::{App, FileSort, InputMode};
+use crate::config::History;
+use crate::filter::load_dlf;
+use crate::parser::{hex_dump, MAX_DIAGNOSTIC_OFFSETS};
+use ratatui::style::Color;
+
+impl App {
+    /// Enter command mode
+    pub fn enter_command_mode(&mut self) {
+        self.input_mode = InputMode::Command;
+        self.command_input = String::new();
+        self.status_message = String::new();
+        self.history_pos = None;
+    }
+
+    /// Exit command mode
+    pub fn exit_command_mode(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.command_input = String::new();
+    }
+
+    /// Handle command input
+    pub fn handle_command_input(&mut self, key: char) {
+        match key {
+            '\n' | '\r' => {
+                // Execute the command on Enter
+                let command = self.command_input.clone();
+                History::push(&mut self.history.command, &command);
+                self.exit_command_mode();
+                self.execute_command(&command);
+            }
+            '\u{8}' | '\u{7f}' => {
+                // Backspace
+                self.command_input.pop();
+            }
+            '\u{1b}' => {
+                // Escape
+                self.exit_command_mode();
+            }
+            _ => {
+                // Add character to input
+                self.command_input.push(key);
+            }
+        }
+    }
+
+    /// Execute a command, reporting the outcome in the status message
+    pub fn execute_command(&mut self, command: &str) {
+        let command = command.trim();
+        let (name, args) = command
+            .split_once(char::is_whitespace)
+            .map_or((command, ""), |(name, args)| (name, args.trim()));
+
+        match name {
+            "" => {}
+            "q" | "quit" => self.exit(),
+            "filter" => {
+                if let Err(e) = self.apply_filter_command(args) {
+                    self.status_message = format!("Invalid filter: {}", e);
+                }
+            }
+            "loaddlf" => self.load_dlf(args),
+            "dump" => self.dump_payload(args),
+            "export" => self.export(args),
+            "ids" => match args {
+                "case" | "nocase" => self.set_case_insensitive_ids(args == "nocase"),
+                _ => self.status_message = format!("Usage: :ids case|nocase, got '{}'", args),
+            },
+            "count" => match self.count_matches(args) {
+                Ok(count) => {
+                    self.status_message = format!(
+                        "{} of {} messages match '{}'",
+                        count,
+                        self.filtered_messages.len(),
+                        args
+                    )
+                }
+                Err(e) => self.status_message = e.to_string(),
+            },
+            "anchor" => match args {
+                "" => self.set_anchor(),
+                "clear" => self.clear_anchor(),
+                _ => self.status_message = format!("Usage: :anchor [clear], got '{}'", args),
+            },
+            "context" if args.is_empty() => {
+                if !self.close_context() {
+                    self.status_message = "Usage: :context N".to_string();
+                }
+            }
+            "context" => match args.parse() {
+                Ok(radius) => self.show_context(radius),
+                Err(_) => self.status_message = format!("Usage: :context N, got '{}'", args),
+            },
+            "hl" => self.highlight_command(args),
+            "unhl" if args.is_empty() => {
+                self.status_message = format!("Cleared {} highlights", self.highlights.len());
+                self.highlights.clear();
+            }
+            "unhl" => {
+                self.status_message = if self.remove_highlight(args) {
+                    format!("Stopped highlighting '{}'", args)
+                } else {
+                    format!("'{}' isn't highlighted", args)
+                };
+            }
+            "diagnostics" => self.show_diagnostics(),
+            "reload" => self.reload_files(),
+            "sortfiles" => match FileSort::from_name(args) {
+                Some(sort) => self.set_file_sort(sort),
+                None => {
+                    self.status_message = format!(
+                        "Usage: :sortfiles {}, got '{}'",
+                        FileSort::NAMES.join("|"),
+                        args
+                    )
+                }
+            },
+            _ => self.status_message = format!("Unknown command: {}", name),
+        }
+    }
+
+    /// Set whether filters compare application and context IDs ignoring case
+    fn set_case_insensitive_ids(&mut self, case_insensitive: bool) {
+        let mut criteria = self.filter.clone();
+        criteria.case_insensitive_ids = case_insensitive;
+        self.set_filter(criteria);
+        self.status_message = format!(
+            "Comparing IDs {}, showing {} messages",
+            if case_insensitive {
+                "ignoring case"
+            } else {
+                "exactly"
+            },
+            self.filtered_messages.len()
+        );
+    }
+
+    /// Highlight a pattern in a color (`:hl PATTERN COLOR`), where the color is
+    /// the last word and the pattern may contain spaces
+    fn highlight_command(&mut self, args: &str) {
+        let Some((pattern, color)) = args.rsplit_once(char::is_whitespace) else {
+            self.status_message = format!("Usage: :hl PATTERN COLOR, got '{}'", args);
+            return;
+        };
+        let pattern = pattern.trim_end();
+        let Ok(parsed) = color.parse::<Color>() else {
+            self.status_message = format!(
+                "Unknown color '{}' (expected a name like red or cyan, an index or #rrggbb)",
+                color
+            );
+            return;
+        };
+
+        self.status_message = match self.add_highlight(pattern, parsed) {
+            Ok(()) => format!(
+                "Highlighting '{}' in {} ({} patterns)",
+                pattern,
+                color,
+                self.highlights.len()
+            ),
+            Err(e) => e.to_string(),
+        };
+    }
+
+    /// Report the problems found while indexing the current file: skipped bytes,
+    /// messages that failed to parse and unsupported protocol versions
+    fn show_diagnostics(&mut self) {
+        let (Some(file), Some(index)) = (
+            self.files.get(self.current_file_idx),
+            self.indices.get(self.current_file_idx),
+        ) else {
+            self.status_message = "No file loaded".to_string();
+            return;
+        };
+
+        let format_offsets = |offsets: &[u64], total: usize| {
+            let mut offsets: Vec<String> = offsets
+                .iter()
+                .map(|offset| format!("0x{:x}", offset))
+                .collect();
+            if total > offsets.len() {
+                offsets.push("…".to_string());
+            }
+            offsets.join(", ")
+        };
+
+        let diagnostics = file.diagnostics();
+        let mut problems = Vec::new();
+        if diagnostics.skipped_regions > 0 {
+            problems.push(format!(
+                "{} unreadable regions ({} bytes) at {}",
+                diagnostics.skipped_regions,
+                diagnostics.skipped_bytes,
+                format_offsets(&diagnostics.skipped_offsets, diagnostics.skipped_regions)
+            ));
+        }
+        let unparsable = index.unparsable_messages();
+        if !unparsable.is_empty() {
+            let offsets: Vec<u64> = unparsable
+                .iter()
+                .take(MAX_DIAGNOSTIC_OFFSETS)
+                .filter_map(|&idx| file.message_offset(idx))
+                .collect();
+            problems.push(format!(
+                "{} messages failed to parse at {}",
+                unparsable.len(),
+                format_offsets(&offsets, unparsable.len())
+            ));
+        }
+        for (version, count) in &diagnostics.unsupported_versions {
+            problems.push(format!(
+                "{} messages with unsupported DLT version {}",
+                count, version
+            ));
+        }
+
+        let name = file
+            .path()
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        self.status_message = if problems.is_empty() {
+            format!("{}: no problems found", name)
+        } else {
+            format!("{}: {}", name, problems.join("; "))
+        };
+    }
+
+    /// Write the payload of the selected message to a file (relative to the working
+    /// directory), raw or as a hex dump (`--hex`)
+    fn dump_payload(&mut self, args: &str) {
+        let (hex, path) = match args.strip_prefix("--hex") {
+            Some(path) => (true, path.trim()),
+            None => (false, args),
+        };
+        if path.is_empty() {
+            self.status_message = "Usage: :dump [--hex] PATH".to_string();
+            return;
+        }
+        let Some(msg) = self.selected_message() else {
+            self.status_message = "No message selected".to_string();
+            return;
+        };
+
+        let data = if hex {
+            let mut dump = hex_dump(&msg.payload);
+            dump.push('\n');
+            dump.into_bytes()
+        } else {
+            msg.payload
+        };
+        let path = self.settings.work_path(path);
+        self.status_message = match fs::write(&path, &data) {
+            Ok(()) => format!("Wrote {} bytes to {}", data.len(), path.display()),
+            Err(e) => format!("Error writing {}: {}", path.display(), e),
+        };
+    }
+
+    /// Write the marked range, or all shown messages, to a file (relative to the
+    /// working directory)
+    ///
+    /// The format follows the extension: JSON lines for `.json` and `.jsonl`,
+    /// the list's text format otherwise.
+    fn export(&mut self, path: &str) {
+        if path.is_empty() {
+            self.status_message = "Usage: :export PATH".to_string();
+            return;
+        }
+
+        let path = self.settings.work_path(path);
+        self.start_export(&path);
+    }
+
+    /// Replace the filter with one loaded from a DLT Viewer filter file (.dlf)
+    fn load_dlf(&mut self, path: &str) {
+        if path.is_empty() {
+            self.status_message = "Usage: :loaddlf PATH".to_string();
+            return;
+        }
+
+        match load_dlf(path) {
+            Ok((mut criteria, warnings)) => {
+                criteria.case_insensitive_ids = self.filter.case_insensitive_ids;
+                self.set_filter(criteria);
+                self.status_message = format!(
+                    "Loaded filter from {}, showing {} messages",
+                    path,
+                    self.filtered_messages.len()
+                );
+                if !warnings.is_empty() {
+                    self.status_message
+                        .push_str(&format!(" ({})", warnings.join("; ")));
+                }
+            }
+            Err(e) => self.status_message = format!("Error loading {}: {}", path, e),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+// Application Errors
+//
+// This file defines the errors of the application layer, phrased for the
+// status line rather than in terms of the libraries they come from.
+
+/// Result type of the application layer
+pub type Result<T> = std::result::Result<T, AppError>;
+
+/// Error of the application layer
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    /// A search pattern that isn't a valid regex, with a description of the problem
+    #[error("Invalid search pattern: {0}")]
+    BadPattern(String),
+}
+
+impl From<regex::Error> for AppError {
+    fn from(error: regex::Error) -> Self {
+        AppError::BadPattern(describe_pattern_error(&error))
+    }
+}
+
+/// Describe a regex error in one line, e.g. "unclosed group near '(abc'"
+///
+/// Syntax errors are reported by the regex crate over several lines: the
+/// pattern, carets under the problem and the reason.
+fn describe_pattern_error(error: &regex::Error) -> String {
+    match error {
+        regex::Error::Syntax(text) => {
+            let lines: Vec<&str> = text.lines().collect();
+            let reason = lines
+                .iter()
+                .find_map(|line| line.strip_prefix("error: "))
+                .unwrap_or("syntax error");
+
+            // The pattern is on the line after the heading, with the carets below it
+            let near = match (lines.get(1), lines.get(2)) {
+                (Some(pattern), Some(carets)) => carets
+                    .chars()
+                    .position(|c| c == '^')
+                    .map(|column| pattern.chars().skip(column).collect::<String>()),
+                _ => None,
+            };
+            match near.filter(|near| !near.is_empty()) {
+                Some(near) => format!("{} near '{}'", reason, near),
+                None => reason.to_string(),
+            }
+        }
+        regex::Error::CompiledTooBig(_) => "the pattern is too large".to_string(),
+        _ => error.to_string(),
+    }
+}
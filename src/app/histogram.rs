@@ -0,0 +1,89 @@
+// Histogram
+//
+// This file defines the state of the histogram view, which buckets the
+// filtered messages by timestamp to show bursts and gaps.
+
+use chrono::{DateTime, Utc};
+
+/// Number of bins of the histogram
+pub const HISTOGRAM_BINS: usize = 60;
+
+/// Histogram of the filtered messages over time
+pub struct Histogram {
+    /// Timestamps of the filtered messages, by position
+    timestamps: Vec<DateTime<Utc>>,
+    /// Earliest timestamp
+    start: DateTime<Utc>,
+    /// Latest timestamp
+    end: DateTime<Utc>,
+    /// Number of messages per bin
+    pub counts: Vec<u64>,
+    /// Currently selected bin
+    pub selected: usize,
+}
+
+impl Histogram {
+    /// Bucket timestamps into bins of equal duration (None if there are none)
+    pub fn new(timestamps: Vec<DateTime<Utc>>, bins: usize) -> Option<Self> {
+        let start = *timestamps.iter().min()?;
+        let end = *timestamps.iter().max()?;
+
+        let mut histogram = Self {
+            timestamps,
+            start,
+            end,
+            counts: vec![0; bins.max(1)],
+            selected: 0,
+        };
+        for i in 0..histogram.timestamps.len() {
+            let bin = histogram.bin_of(histogram.timestamps[i]);
+            histogram.counts[bin] += 1;
+        }
+
+        Some(histogram)
+    }
+
+    /// Get the bin a timestamp falls into
+    pub fn bin_of(&self, timestamp: DateTime<Utc>) -> usize {
+        let span = (self.end - self.start)
+            .num_microseconds()
+            .unwrap_or(i64::MAX) as u128
+            + 1;
+        let offset = (timestamp - self.start)
+            .num_microseconds()
+            .unwrap_or(0)
+            .max(0) as u128;
+        ((offset * self.counts.len() as u128 / span) as usize).min(self.counts.len() - 1)
+    }
+
+    /// Get the start and end time of a bin
+    pub fn bin_range(&self, bin: usize) -> (DateTime<Utc>, DateTime<Utc>) {
+        let step = (self.end - self.start) / self.counts.len() as i32;
+        let bin_start = self.start + step * bin as i32;
+        let bin_end = if bin + 1 == self.counts.len() {
+            self.end
+        } else {
+            bin_start + step
+        };
+        (bin_start, bin_end)
+    }
+
+    /// Select the previous bin
+    pub fn move_left(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Select the next bin
+    pub fn move_right(&mut self) {
+        if self.selected + 1 < self.counts.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Get the first position in the filtered messages that falls into a bin
+    pub fn first_position(&self, bin: usize) -> Option<usize> {
+        self.timestamps
+            .iter()
+            .position(|&timestamp| self.bin_of(timestamp) == bin)
+    }
+}
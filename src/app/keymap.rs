@@ -0,0 +1,601 @@
+// Key Map
+//
+// This file defines the actions that can be bound to keys in normal mode,
+// and the key map that binds them.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::app::App;
+
+/// Action triggered by a key in normal mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    MoveUp,
+    MoveDown,
+    HalfPageUp,
+    HalfPageDown,
+    MoveToTop,
+    MoveToBottom,
+    ScrollLeft,
+    ScrollRight,
+    ScrollToStart,
+    ScrollToEnd,
+    ToggleDetail,
+    ShowHelp,
+    ToggleTruncate,
+    ToggleHex,
+    ToggleRawHeader,
+    ToggleJson,
+    ToggleRelativeTime,
+    ToggleReversed,
+    ToggleLineNumbers,
+    CommandMode,
+    PrevFile,
+    ToggleMerged,
+    OpenFile,
+    ToggleFollow,
+    ToggleBrowser,
+    WidenBrowser,
+    NarrowBrowser,
+    SearchMode,
+    NextMatch,
+    PrevMatch,
+    FilterMode,
+    FilterApp,
+    FilterContext,
+    ExcludeApp,
+    ExcludeContext,
+    ClearExclusions,
+    ToggleErrorsOnly,
+    ToggleCase,
+    NextSameApp,
+    PrevSameApp,
+    NextSameContext,
+    PrevSameContext,
+    ToggleRange,
+    ToggleMeasure,
+    ShowHistogram,
+}
+
+impl Action {
+    /// All actions, in the order they are listed in the help
+    pub const ALL: [Action; 46] = [
+        Action::Quit,
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::HalfPageUp,
+        Action::HalfPageDown,
+        Action::MoveToTop,
+        Action::MoveToBottom,
+        Action::ScrollLeft,
+        Action::ScrollRight,
+        Action::ScrollToStart,
+        Action::ScrollToEnd,
+        Action::ToggleDetail,
+        Action::ShowHelp,
+        Action::ToggleTruncate,
+        Action::ToggleHex,
+        Action::ToggleRawHeader,
+        Action::ToggleJson,
+        Action::ToggleRelativeTime,
+        Action::ToggleReversed,
+        Action::ToggleLineNumbers,
+        Action::CommandMode,
+        Action::PrevFile,
+        Action::ToggleMerged,
+        Action::OpenFile,
+        Action::ToggleFollow,
+        Action::ToggleBrowser,
+        Action::WidenBrowser,
+        Action::NarrowBrowser,
+        Action::SearchMode,
+        Action::NextMatch,
+        Action::PrevMatch,
+        Action::FilterMode,
+        Action::FilterApp,
+        Action::FilterContext,
+        Action::ExcludeApp,
+        Action::ExcludeContext,
+        Action::ClearExclusions,
+        Action::ToggleErrorsOnly,
+        Action::ToggleCase,
+        Action::NextSameApp,
+        Action::PrevSameApp,
+        Action::NextSameContext,
+        Action::PrevSameContext,
+        Action::ToggleRange,
+        Action::ToggleMeasure,
+        Action::ShowHistogram,
+    ];
+
+    /// Get the name of the action as used in the `[keys]` config table
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::MoveUp => "move_up",
+            Action::MoveDown => "move_down",
+            Action::HalfPageUp => "half_page_up",
+            Action::HalfPageDown => "half_page_down",
+            Action::MoveToTop => "move_to_top",
+            Action::MoveToBottom => "move_to_bottom",
+            Action::ScrollLeft => "scroll_left",
+            Action::ScrollRight => "scroll_right",
+            Action::ScrollToStart => "scroll_to_start",
+            Action::ScrollToEnd => "scroll_to_end",
+            Action::ToggleDetail => "toggle_detail",
+            Action::ShowHelp => "show_help",
+            Action::ToggleTruncate => "toggle_truncate",
+            Action::ToggleHex => "toggle_hex",
+            Action::ToggleRawHeader => "toggle_raw_header",
+            Action::ToggleJson => "toggle_json",
+            Action::ToggleRelativeTime => "toggle_relative_time",
+            Action::ToggleReversed => "toggle_reversed",
+            Action::ToggleLineNumbers => "toggle_line_numbers",
+            Action::CommandMode => "command_mode",
+            Action::PrevFile => "prev_file",
+            Action::ToggleMerged => "toggle_merged",
+            Action::OpenFile => "open_file",
+            Action::ToggleFollow => "toggle_follow",
+            Action::ToggleBrowser => "toggle_browser",
+            Action::WidenBrowser => "widen_browser",
+            Action::NarrowBrowser => "narrow_browser",
+            Action::SearchMode => "search_mode",
+            Action::NextMatch => "next_match",
+            Action::PrevMatch => "prev_match",
+            Action::FilterMode => "filter_mode",
+            Action::FilterApp => "filter_app",
+            Action::FilterContext => "filter_context",
+            Action::ExcludeApp => "exclude_app",
+            Action::ExcludeContext => "exclude_context",
+            Action::ClearExclusions => "clear_exclusions",
+            Action::ToggleErrorsOnly => "toggle_errors_only",
+            Action::ToggleCase => "toggle_case",
+            Action::NextSameApp => "next_same_app",
+            Action::PrevSameApp => "prev_same_app",
+            Action::NextSameContext => "next_same_context",
+            Action::PrevSameContext => "prev_same_context",
+            Action::ToggleRange => "toggle_range",
+            Action::ToggleMeasure => "toggle_measure",
+            Action::ShowHistogram => "show_histogram",
+        }
+    }
+
+    /// Get the group the action is listed under in the help
+    pub fn category(&self) -> Category {
+        match self {
+            Action::Quit => Category::Other,
+            Action::MoveUp => Category::Navigation,
+            Action::MoveDown => Category::Navigation,
+            Action::HalfPageUp => Category::Navigation,
+            Action::HalfPageDown => Category::Navigation,
+            Action::MoveToTop => Category::Navigation,
+            Action::MoveToBottom => Category::Navigation,
+            Action::ScrollLeft => Category::Navigation,
+            Action::ScrollRight => Category::Navigation,
+            Action::ScrollToStart => Category::Navigation,
+            Action::ScrollToEnd => Category::Navigation,
+            Action::ToggleDetail => Category::View,
+            Action::ShowHelp => Category::View,
+            Action::ToggleTruncate => Category::View,
+            Action::ToggleHex => Category::View,
+            Action::ToggleRawHeader => Category::View,
+            Action::ToggleJson => Category::View,
+            Action::ToggleRelativeTime => Category::View,
+            Action::ToggleReversed => Category::View,
+            Action::ToggleLineNumbers => Category::View,
+            Action::CommandMode => Category::Other,
+            Action::PrevFile => Category::Files,
+            Action::ToggleMerged => Category::Files,
+            Action::OpenFile => Category::Files,
+            Action::ToggleFollow => Category::Files,
+            Action::ToggleBrowser => Category::Files,
+            Action::WidenBrowser => Category::Files,
+            Action::NarrowBrowser => Category::Files,
+            Action::SearchMode => Category::Search,
+            Action::NextMatch => Category::Search,
+            Action::PrevMatch => Category::Search,
+            Action::FilterMode => Category::Search,
+            Action::FilterApp => Category::Search,
+            Action::FilterContext => Category::Search,
+            Action::ExcludeApp => Category::Search,
+            Action::ExcludeContext => Category::Search,
+            Action::ClearExclusions => Category::Search,
+            Action::ToggleErrorsOnly => Category::Search,
+            Action::ToggleCase => Category::Search,
+            Action::NextSameApp => Category::Navigation,
+            Action::PrevSameApp => Category::Navigation,
+            Action::NextSameContext => Category::Navigation,
+            Action::PrevSameContext => Category::Navigation,
+            Action::ToggleRange => Category::Other,
+            Action::ToggleMeasure => Category::Other,
+            Action::ShowHistogram => Category::View,
+        }
+    }
+
+    /// Get a short description of the action, as shown in the help
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::MoveUp => "Move up (also in detail view)",
+            Action::MoveDown => "Move down (also in detail view)",
+            Action::HalfPageUp => "Half page up",
+            Action::HalfPageDown => "Half page down",
+            Action::MoveToTop => "Go to top",
+            Action::MoveToBottom => "Go to bottom (resumes following)",
+            Action::ScrollLeft => "Scroll payloads left",
+            Action::ScrollRight => "Scroll payloads right",
+            Action::ScrollToStart => "Scroll payloads back to their start",
+            Action::ScrollToEnd => "Scroll to the end of the longest visible payload",
+            Action::ToggleDetail => "Toggle detail view",
+            Action::ShowHelp => "Show/hide help",
+            Action::ToggleTruncate => "Toggle truncation of long payloads",
+            Action::ToggleHex => "Toggle hex dump in the detail view",
+            Action::ToggleRawHeader => "Toggle raw header bytes in the detail view",
+            Action::ToggleJson => "Toggle pretty-printing of JSON payloads in the detail view",
+            Action::ToggleRelativeTime => "Toggle relative timestamps",
+            Action::ToggleReversed => "Toggle showing the newest messages first",
+            Action::ToggleLineNumbers => "Toggle the column of message indices in their file",
+            Action::CommandMode => "Command mode",
+            Action::PrevFile => "Previous file",
+            Action::ToggleMerged => "Toggle merged view of all files",
+            Action::OpenFile => "Open a file",
+            Action::ToggleFollow => "Follow growing files",
+            Action::ToggleBrowser => "Show / hide the file browser",
+            Action::WidenBrowser => "Widen the file browser",
+            Action::NarrowBrowser => "Narrow the file browser",
+            Action::SearchMode => "Search",
+            Action::NextMatch => "Next search result",
+            Action::PrevMatch => "Previous search result",
+            Action::FilterMode => "Filter mode",
+            Action::FilterApp => "Filter to selected message's application",
+            Action::FilterContext => "Filter to selected message's context",
+            Action::ExcludeApp => "Hide selected message's application",
+            Action::ExcludeContext => "Hide selected message's context",
+            Action::ClearExclusions => "Show hidden applications and contexts again",
+            Action::ToggleErrorsOnly => "Toggle showing only errors (and fatal messages)",
+            Action::ToggleCase => "Toggle case sensitivity for search",
+            Action::NextSameApp => "Next message of the same application",
+            Action::PrevSameApp => "Previous message of the same application",
+            Action::NextSameContext => "Next message of the same context",
+            Action::PrevSameContext => "Previous message of the same context",
+            Action::ToggleRange => "Start or clear a marked range",
+            Action::ToggleMeasure => "Mark a message to measure the time from, or clear the mark",
+            Action::ShowHistogram => "Histogram of messages over time (←/→, Enter jumps)",
+        }
+    }
+
+    /// Parse an action from its name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+}
+
+/// Group of actions in the help
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Navigation,
+    View,
+    Files,
+    Search,
+    Other,
+}
+
+impl Category {
+    /// All categories, in the order they are listed in the help
+    pub const ALL: [Category; 5] = [
+        Category::Navigation,
+        Category::View,
+        Category::Files,
+        Category::Search,
+        Category::Other,
+    ];
+
+    /// Get the heading of the category in the help
+    pub fn title(&self) -> &'static str {
+        match self {
+            Category::Navigation => "Navigation",
+            Category::View => "View Controls",
+            Category::Files => "Files",
+            Category::Search => "Filtering and Searching",
+            Category::Other => "Other",
+        }
+    }
+}
+
+/// Key with modifiers, as bound in the key map
+pub type Key = (KeyCode, KeyModifiers);
+
+/// Mapping from keys to actions
+#[derive(Debug, Clone)]
+pub struct KeyMap {
+    /// Bound keys
+    bindings: HashMap<Key, Action>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let none = KeyModifiers::NONE;
+        let ctrl = KeyModifiers::CONTROL;
+        let bindings = [
+            (KeyCode::Char('q'), none, Action::Quit),
+            (KeyCode::Up, none, Action::MoveUp),
+            (KeyCode::Char('k'), none, Action::MoveUp),
+            (KeyCode::Char('K'), none, Action::MoveUp),
+            (KeyCode::Char('p'), ctrl, Action::MoveUp),
+            (KeyCode::Down, none, Action::MoveDown),
+            (KeyCode::Char('j'), none, Action::MoveDown),
+            (KeyCode::Char('J'), none, Action::MoveDown),
+            (KeyCode::Char('n'), ctrl, Action::MoveDown),
+            (KeyCode::Char('u'), ctrl, Action::HalfPageUp),
+            (KeyCode::Char('d'), ctrl, Action::HalfPageDown),
+            (KeyCode::Home, none, Action::MoveToTop),
+            (KeyCode::Char('g'), none, Action::MoveToTop),
+            (KeyCode::End, none, Action::MoveToBottom),
+            (KeyCode::Char('G'), none, Action::MoveToBottom),
+            (KeyCode::Left, none, Action::ScrollLeft),
+            (KeyCode::Right, none, Action::ScrollRight),
+            (KeyCode::Char('0'), none, Action::ScrollToStart),
+            (KeyCode::Char('$'), none, Action::ScrollToEnd),
+            (KeyCode::Enter, none, Action::ToggleDetail),
+            (KeyCode::Char('h'), none, Action::ShowHelp),
+            (KeyCode::Char('?'), none, Action::ShowHelp),
+            (KeyCode::Char('w'), none, Action::ToggleTruncate),
+            (KeyCode::Char('H'), none, Action::ToggleHex),
+            (KeyCode::Char('R'), none, Action::ToggleRawHeader),
+            (KeyCode::Char('P'), none, Action::ToggleJson),
+            (KeyCode::Char('T'), none, Action::ToggleRelativeTime),
+            (KeyCode::Char('r'), none, Action::ToggleReversed),
+            (KeyCode::Char('#'), none, Action::ToggleLineNumbers),
+            (KeyCode::Char(':'), none, Action::CommandMode),
+            (KeyCode::Char('p'), none, Action::PrevFile),
+            (KeyCode::Char('m'), none, Action::ToggleMerged),
+            (KeyCode::Char('o'), none, Action::OpenFile),
+            (KeyCode::Char('F'), none, Action::ToggleFollow),
+            (KeyCode::Char('b'), none, Action::ToggleBrowser),
+            (KeyCode::Char('>'), none, Action::WidenBrowser),
+            (KeyCode::Char('<'), none, Action::NarrowBrowser),
+            (KeyCode::Char('/'), none, Action::SearchMode),
+            (KeyCode::Char('n'), none, Action::NextMatch),
+            (KeyCode::Char('N'), none, Action::PrevMatch),
+            (KeyCode::Char('f'), none, Action::FilterMode),
+            (KeyCode::Char('a'), none, Action::FilterApp),
+            (KeyCode::Char('x'), none, Action::FilterContext),
+            (KeyCode::Char('A'), none, Action::ExcludeApp),
+            (KeyCode::Char('X'), none, Action::ExcludeContext),
+            (KeyCode::Char('U'), none, Action::ClearExclusions),
+            (KeyCode::Char('e'), none, Action::ToggleErrorsOnly),
+            (KeyCode::Char('i'), none, Action::ToggleCase),
+            (KeyCode::Char(']'), none, Action::NextSameApp),
+            (KeyCode::Char('['), none, Action::PrevSameApp),
+            (KeyCode::Char('}'), none, Action::NextSameContext),
+            (KeyCode::Char('{'), none, Action::PrevSameContext),
+            (KeyCode::Char('V'), none, Action::ToggleRange),
+            (KeyCode::Char('M'), none, Action::ToggleMeasure),
+            (KeyCode::Char('t'), none, Action::ShowHistogram),
+        ]
+        .into_iter()
+        .map(|(code, modifiers, action)| ((code, modifiers), action))
+        .collect();
+
+        Self { bindings }
+    }
+}
+
+impl KeyMap {
+    /// Get the action bound to a key event
+    pub fn action(&self, key: KeyEvent) -> Option<Action> {
+        self.bindings
+            .get(&normalize(key.code, key.modifiers))
+            .copied()
+    }
+
+    /// Get the keys bound to an action, plain characters first
+    pub fn keys_for(&self, action: Action) -> Vec<Key> {
+        let mut keys: Vec<Key> = self
+            .bindings
+            .iter()
+            .filter(|(_, &bound)| bound == action)
+            .map(|(&key, _)| key)
+            .collect();
+        keys.sort_by_key(|&(code, modifiers)| {
+            (
+                !modifiers.is_empty(),
+                !matches!(code, KeyCode::Char(_)),
+                key_name((code, modifiers)),
+            )
+        });
+        keys
+    }
+
+    /// Bind a key to an action, replacing any previous binding of the key
+    pub fn bind(&mut self, key: Key, action: Action) {
+        self.bindings.insert(normalize(key.0, key.1), action);
+    }
+
+    /// Remove the binding of a key
+    pub fn unbind(&mut self, key: Key) {
+        self.bindings.remove(&normalize(key.0, key.1));
+    }
+
+    /// Apply overrides from the `[keys]` config table (key name to action name)
+    ///
+    /// The action `none` removes a binding. Returns a warning for each entry
+    /// that couldn't be applied.
+    pub fn apply_overrides(&mut self, overrides: &BTreeMap<String, String>) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        for (key_name, action_name) in overrides {
+            let Some(key) = parse_key(key_name) else {
+                warnings.push(format!("Ignoring unknown key '{}'", key_name));
+                continue;
+            };
+
+            if action_name == "none" {
+                self.unbind(key);
+            } else if let Some(action) = Action::from_name(action_name) {
+                self.bind(key, action);
+            } else {
+                warnings.push(format!(
+                    "Ignoring unknown action '{}' for key '{}'",
+                    action_name, key_name
+                ));
+            }
+        }
+
+        warnings
+    }
+}
+
+/// Drop the Shift modifier of character keys, as it is already part of the character
+fn normalize(code: KeyCode, modifiers: KeyModifiers) -> Key {
+    match code {
+        KeyCode::Char(_) => (code, modifiers - KeyModifiers::SHIFT),
+        _ => (code, modifiers),
+    }
+}
+
+/// Format a key for display, e.g. `j`, `Ctrl+d` or `↑`
+pub fn key_name((code, modifiers): Key) -> String {
+    let mut name = String::new();
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        name.push_str("Ctrl+");
+    }
+    if modifiers.contains(KeyModifiers::ALT) {
+        name.push_str("Alt+");
+    }
+    if modifiers.contains(KeyModifiers::SHIFT) {
+        name.push_str("Shift+");
+    }
+
+    match code {
+        KeyCode::Char(' ') => name.push_str("Space"),
+        KeyCode::Char(c) => name.push(c),
+        KeyCode::Up => name.push('↑'),
+        KeyCode::Down => name.push('↓'),
+        KeyCode::Left => name.push('←'),
+        KeyCode::Right => name.push('→'),
+        KeyCode::Home => name.push_str("Home"),
+        KeyCode::End => name.push_str("End"),
+        KeyCode::PageUp => name.push_str("PgUp"),
+        KeyCode::PageDown => name.push_str("PgDn"),
+        KeyCode::Enter => name.push_str("Enter"),
+        KeyCode::Esc => name.push_str("Esc"),
+        KeyCode::Tab => name.push_str("Tab"),
+        KeyCode::Backspace => name.push_str("Backspace"),
+        KeyCode::Delete => name.push_str("Delete"),
+        KeyCode::F(n) => name.push_str(&format!("F{}", n)),
+        other => name.push_str(&format!("{:?}", other)),
+    }
+
+    name
+}
+
+/// Parse a key name such as `j`, `G`, `ctrl+d`, `alt+enter`, `pagedown` or `f5`
+fn parse_key(name: &str) -> Option<Key> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut parts: Vec<&str> = name.split('+').collect();
+    let mut key = parts.pop()?;
+
+    // A trailing '+' is the plus key itself (e.g. "ctrl++")
+    if key.is_empty() && name.ends_with('+') {
+        parts.pop();
+        key = "+";
+    }
+
+    for modifier in parts {
+        modifiers |= match modifier.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return None,
+        };
+    }
+
+    let mut chars = key.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match key.to_ascii_lowercase().as_str() {
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "delete" => KeyCode::Delete,
+            "space" => KeyCode::Char(' '),
+            other => {
+                let n = other.strip_prefix('f')?.parse().ok()?;
+                KeyCode::F(n)
+            }
+        },
+    };
+
+    Some((code, modifiers))
+}
+
+impl App {
+    /// Perform an action bound to a key
+    pub fn perform(&mut self, action: Action) {
+        match action {
+            Action::Quit => self.exit(),
+            Action::MoveUp => self.move_up(),
+            Action::MoveDown => self.move_down(),
+            Action::HalfPageUp => self.half_page_up(),
+            Action::HalfPageDown => self.half_page_down(),
+            Action::MoveToTop => self.move_to_top(),
+            Action::MoveToBottom => self.move_to_bottom(),
+            Action::ScrollLeft => self.scroll_left(),
+            Action::ScrollRight => self.scroll_right(),
+            Action::ScrollToStart => self.scroll_to_start(),
+            Action::ScrollToEnd => self.scroll_to_end(),
+            Action::ToggleDetail => self.toggle_view_mode(),
+            Action::ShowHelp => self.show_help(),
+            Action::ToggleTruncate => self.toggle_truncate_payload(),
+            Action::ToggleHex => self.toggle_hex(),
+            Action::ToggleRawHeader => self.toggle_raw_header(),
+            Action::ToggleJson => self.toggle_pretty_json(),
+            Action::ToggleRelativeTime => self.toggle_relative_timestamps(),
+            Action::ToggleReversed => self.toggle_reversed(),
+            Action::ToggleLineNumbers => self.toggle_line_numbers(),
+            Action::CommandMode => self.enter_command_mode(),
+            Action::PrevFile => self.prev_file(),
+            Action::ToggleMerged => self.toggle_merged(),
+            Action::OpenFile => self.open_picker(),
+            Action::ToggleFollow => self.toggle_follow_mode(),
+            Action::ToggleBrowser => self.toggle_browser(),
+            Action::WidenBrowser => self.widen_browser(),
+            Action::NarrowBrowser => self.narrow_browser(),
+            Action::SearchMode => self.enter_search_mode(),
+            Action::NextMatch => self.next_search_result(),
+            Action::PrevMatch => self.prev_search_result(),
+            Action::FilterMode => self.enter_filter_mode(),
+            Action::FilterApp => self.filter_to_selected_app(),
+            Action::FilterContext => self.filter_to_selected_context(),
+            Action::ExcludeApp => self.exclude_selected_app(),
+            Action::ExcludeContext => self.exclude_selected_context(),
+            Action::ClearExclusions => self.clear_exclusions(),
+            Action::ToggleErrorsOnly => self.toggle_errors_only(),
+            Action::ToggleCase => {
+                if let Err(e) = self.toggle_case_sensitivity() {
+                    self.status_message = e.to_string();
+                }
+            }
+            Action::NextSameApp => self.next_same_app(),
+            Action::PrevSameApp => self.prev_same_app(),
+            Action::NextSameContext => self.next_same_context(),
+            Action::PrevSameContext => self.prev_same_context(),
+            Action::ToggleRange => self.toggle_range(),
+            Action::ToggleMeasure => self.toggle_measure(),
+            Action::ShowHistogram => self.open_histogram(),
+        }
+    }
+}
@@ -2,6 +2,15 @@
 //
 // This module defines the main application state and logic.
 
+mod command;
+mod error;
+mod histogram;
+mod keymap;
+mod picker;
 mod state;
 
-pub use state::{App, InputMode, ViewMode};
+pub use error::Result as AppResult;
+pub use histogram::{Histogram, HISTOGRAM_BINS};
+pub use keymap::{key_name, Action, Category, KeyMap};
+pub use picker::{FilePicker, PickerEntry};
+pub use state::{App, FileSort, InputMode, ViewMode};
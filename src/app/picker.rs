@@ -0,0 +1,146 @@
+// File Picker
+//
+// This file defines the state of the file picker shown when no files are
+// given on the command line.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Entry in the file picker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PickerEntry {
+    /// Recently opened file
+    Recent(PathBuf),
+    /// Parent of the current directory
+    Parent,
+    /// Subdirectory of the current directory
+    Directory(PathBuf),
+    /// DLT file in the current directory
+    File(PathBuf),
+}
+
+impl PickerEntry {
+    /// Get the label shown for the entry
+    pub fn label(&self) -> String {
+        match self {
+            PickerEntry::Recent(path) => path.display().to_string(),
+            PickerEntry::Parent => "../".to_string(),
+            PickerEntry::Directory(path) => format!("{}/", file_name(path)),
+            PickerEntry::File(path) => file_name(path),
+        }
+    }
+}
+
+/// File picker state
+pub struct FilePicker {
+    /// Directory being browsed
+    pub dir: PathBuf,
+    /// Recently opened files, listed above the directory contents
+    pub recent_files: Vec<PathBuf>,
+    /// Entries shown in the picker
+    pub entries: Vec<PickerEntry>,
+    /// Currently selected entry index
+    pub selected: usize,
+}
+
+impl FilePicker {
+    /// Create a new file picker browsing the given directory
+    pub fn new(dir: PathBuf, recent_files: &[PathBuf]) -> Self {
+        let mut picker = Self {
+            dir,
+            recent_files: recent_files
+                .iter()
+                .filter(|p| p.is_file())
+                .cloned()
+                .collect(),
+            entries: Vec::new(),
+            selected: 0,
+        };
+        picker.refresh();
+        picker
+    }
+
+    /// Re-read the entries of the current directory
+    pub fn refresh(&mut self) {
+        let mut dirs = Vec::new();
+        let mut files = Vec::new();
+
+        if let Ok(read_dir) = fs::read_dir(&self.dir) {
+            for entry in read_dir.flatten() {
+                let path = entry.path();
+                if file_name(&path).starts_with('.') {
+                    continue;
+                }
+
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if is_dlt_file(&path) {
+                    files.push(path);
+                }
+            }
+        }
+
+        dirs.sort();
+        files.sort();
+
+        self.entries = self
+            .recent_files
+            .iter()
+            .cloned()
+            .map(PickerEntry::Recent)
+            .collect();
+        if self.dir.parent().is_some() {
+            self.entries.push(PickerEntry::Parent);
+        }
+        self.entries
+            .extend(dirs.into_iter().map(PickerEntry::Directory));
+        self.entries
+            .extend(files.into_iter().map(PickerEntry::File));
+
+        self.selected = 0;
+    }
+
+    /// Get the selected entry
+    pub fn selected_entry(&self) -> Option<&PickerEntry> {
+        self.entries.get(self.selected)
+    }
+
+    /// Move the selection up
+    pub fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Move the selection down
+    pub fn move_down(&mut self) {
+        if self.selected + 1 < self.entries.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Browse a different directory
+    pub fn change_dir(&mut self, dir: PathBuf) {
+        self.dir = dir;
+        self.refresh();
+    }
+
+    /// Browse the parent directory
+    pub fn parent_dir(&mut self) {
+        if let Some(parent) = self.dir.parent() {
+            self.change_dir(parent.to_path_buf());
+        }
+    }
+}
+
+/// Get the file name of a path as a string
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Check if a path has the `.dlt` extension
+fn is_dlt_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("dlt"))
+}
@@ -2,15 +2,51 @@
 //
 // This file defines the main application state and logic.
 
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use ratatui::style::Color;
+use rayon::prelude::*;
 use regex::Regex;
 
-use crate::filter::{FilterCriteria, FilterEngine};
-use crate::parser::{DltFile, DltMessage, Index, Result as ParserResult};
+use crate::app::{AppResult, FilePicker, Histogram, KeyMap, PickerEntry, HISTOGRAM_BINS};
+use crate::config::{History, Session, SessionFile, Settings, Timezone};
+use crate::export::{write_messages, OutputFormat};
+use crate::filter::{
+    parse_time_of_day, Error as FilterError, FilterCriteria, FilterCriteriaConfig, FilterEngine,
+    Result as FilterResult,
+};
+use crate::parser::{
+    DltFile, DltMessage, FileChange, Index, LogLevel, PayloadDecoders, Result as ParserResult,
+    Utf8Decoder,
+};
 use crate::search::SearchEngine;
+use crate::ui::{Event, Theme};
+
+/// Number of characters to scroll horizontally per step
+const H_SCROLL_STEP: usize = 8;
+
+/// Number of filtered messages searched between progress updates
+const SEARCH_CHUNK_SIZE: usize = 10_000;
+
+/// Number of filtered messages scanned per key or tick while typing a search
+const INCREMENTAL_SEARCH_CHUNK_SIZE: usize = 50_000;
+
+/// Number of messages exported between progress updates
+const EXPORT_CHUNK_SIZE: usize = 10_000;
+
+/// Step (in percent of the width) by which the file browser is resized
+const BROWSER_RESIZE_STEP: u16 = 5;
+
+/// Smallest and largest width of the file browser, in percent of the width
+const BROWSER_RATIO_RANGE: (u16, u16) = (10, 60);
 
 /// View mode for the application
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -21,6 +57,10 @@ pub enum ViewMode {
     Detail,
     /// Help view showing keyboard shortcuts
     Help,
+    /// File picker for opening files
+    Picker,
+    /// Histogram of the filtered messages over time
+    Histogram,
 }
 
 /// Input mode for the application
@@ -32,90 +72,507 @@ pub enum InputMode {
     Search,
     /// Filter mode (typing a filter pattern)
     Filter,
+    /// Command mode (typing a `:` command)
+    Command,
+}
+
+/// Order of the files in the file browser
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileSort {
+    /// Order in which the files were loaded
+    Load,
+    /// File name
+    Name,
+    /// Modification time, oldest first
+    Modified,
+    /// Number of messages, fewest first
+    Messages,
+}
+
+impl FileSort {
+    /// Names of the file orders
+    pub const NAMES: [&'static str; 4] = ["load", "name", "modified", "messages"];
+
+    /// Get a file order by name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "load" => Some(FileSort::Load),
+            "name" => Some(FileSort::Name),
+            "modified" | "mtime" => Some(FileSort::Modified),
+            "messages" | "count" => Some(FileSort::Messages),
+            _ => None,
+        }
+    }
+
+    /// Get the name of the file order
+    pub fn name(&self) -> &'static str {
+        match self {
+            FileSort::Load => "load",
+            FileSort::Name => "name",
+            FileSort::Modified => "modified",
+            FileSort::Messages => "messages",
+        }
+    }
+}
+
+/// View state of a file, restored when switching back to it
+#[derive(Debug, Clone, Copy)]
+struct FileViewState {
+    /// Index of the selected message in the file
+    message_idx: usize,
+    /// Horizontal scroll offset
+    h_scroll: usize,
+}
+
+/// A search running on a background thread
+struct SearchJob {
+    /// Identifier telling the results of this search apart from earlier ones
+    id: u64,
+    /// Flag telling the search thread to stop
+    cancel: Arc<AtomicBool>,
+    /// Fraction (0.0 to 1.0) of the messages searched so far
+    progress: f64,
+    /// Pattern being searched for
+    pattern: String,
+    /// Position to select once it is found (None selects the first result)
+    keep_selection: Option<usize>,
+    /// Whether a result still has to be selected
+    selection_pending: bool,
+}
+
+/// Filtered view saved while the unfiltered context of a message is shown
+struct ContextView {
+    /// Number of messages shown before and after the message
+    radius: usize,
+    /// Filtered message indices of the saved view
+    filtered_messages: Vec<usize>,
+    /// Selected position in the saved view
+    selected_message_idx: usize,
+    /// Search results in the saved view
+    search_results: Vec<usize>,
+    /// Current search result index in the saved view
+    current_search_idx: usize,
+    /// Start of the marked range in the saved view
+    range_anchor: Option<usize>,
+}
+
+/// An export running on a background thread
+struct ExportJob {
+    /// Identifier telling the events of this export apart from earlier ones
+    id: u64,
+    /// Flag telling the export thread to stop
+    cancel: Arc<AtomicBool>,
+    /// File being written
+    path: PathBuf,
+    /// Number of messages written so far
+    written: usize,
+    /// Number of messages to write
+    total: usize,
 }
 
 /// Application state
 pub struct App {
+    /// Application settings
+    pub settings: Settings,
+    /// Key bindings in normal mode
+    pub keymap: KeyMap,
     /// List of loaded DLT files
     pub files: Vec<Arc<DltFile>>,
     /// Indices for each file
     pub indices: Vec<Arc<Index>>,
     /// Currently selected file index
     pub current_file_idx: usize,
+    /// Order of the files in the file browser
+    pub file_sort: FileSort,
+    /// Indices into `files` in the order the file browser shows them
+    pub file_order: Vec<usize>,
+    /// Width of the file browser in the list view, in percent of the width
+    pub browser_ratio: u16,
+    /// Whether the file browser is shown in the list view; when unset it is
+    /// hidden while only one file is open
+    pub browser_visible: Option<bool>,
+    /// Files being loaded in the background, with their progress (0.0 to 1.0)
+    pub loading: Vec<(PathBuf, f64)>,
+    /// Overall progress of the files being loaded (1.0 when nothing is loading)
+    pub indexing_progress: f64,
+    /// View state of the files not currently shown, by file index
+    file_view_states: HashMap<usize, FileViewState>,
+    /// Show all files merged into one time-ordered view
+    pub merged: bool,
+    /// Messages of all files as (file index, message index), ordered by timestamp
+    pub merged_messages: Vec<(usize, usize)>,
     /// Filter criteria
     pub filter: FilterCriteria,
     /// Filter engine
     pub filter_engine: Option<FilterEngine>,
-    /// Filtered message indices
+    /// Filter to restore when leaving the errors-only view (Some while it is shown)
+    pub errors_only_saved_filter: Option<FilterCriteria>,
+    /// Filtered message indices (into `merged_messages` when merged)
     pub filtered_messages: Vec<usize>,
+    /// Filtered view to return to while the context of a message is shown
+    context_view: Option<ContextView>,
     /// Currently selected message index
     pub selected_message_idx: usize,
     /// Current view mode
     pub view_mode: ViewMode,
     /// Current input mode
     pub input_mode: InputMode,
+    /// File picker state (while the picker is open)
+    pub picker: Option<FilePicker>,
+    /// Histogram state (while the histogram is open)
+    pub histogram: Option<Histogram>,
     /// Search engine
     pub search_engine: Option<SearchEngine>,
     /// Search pattern
     pub search_pattern: Option<Regex>,
-    /// Search results (indices into filtered_messages)
+    /// Search pattern as typed, before the case sensitivity is applied
+    pub search_query: Option<String>,
+    /// Further patterns highlighted in the list, each in its own color, after
+    /// the search pattern
+    pub highlights: Vec<(Regex, Color)>,
+    /// Search results as message indices (like the entries of `filtered_messages`)
+    /// in ascending order, so they stay valid when the filter changes; only changed
+    /// through `add_search_results` and `clear_search_results` so the set below
+    /// stays in sync
     pub search_results: Vec<usize>,
+    /// Search results as a set, for checking whether a message is a result
+    search_result_set: HashSet<usize>,
     /// Current search result index
     pub current_search_idx: usize,
+    /// Selection before entering search mode, restored on cancel
+    pub search_origin: usize,
+    /// Scan for the first match of the pattern typed so far, as the engine and
+    /// the number of messages after `search_origin` scanned
+    incremental_scan: Option<(SearchEngine, usize)>,
     /// Case sensitive search flag
     pub case_sensitive_search: bool,
+    /// Search running in the background
+    search_job: Option<SearchJob>,
+    /// Identifier of the next background search
+    next_search_id: u64,
+    /// Export running in the background
+    export_job: Option<ExportJob>,
+    /// Identifier of the next background export
+    next_export_id: u64,
+    /// Sender for posting events from background threads (searches run
+    /// synchronously without one)
+    pub events: Option<mpsc::Sender<Event>>,
     /// Command input buffer
     pub command_input: String,
+    /// Search and filter input history
+    pub history: History,
+    /// Position in the history while browsing it (None while editing new input)
+    pub history_pos: Option<usize>,
+    /// Input typed before browsing the history, restored when browsing past the end
+    pub history_draft: String,
     /// Status message
     pub status_message: String,
+    /// Number of message rows visible in the log list
+    pub viewport_height: usize,
+    /// Horizontal scroll offset of the payloads in the list, in characters
+    pub h_scroll: usize,
+    /// Truncate long payloads in the list (otherwise they are clipped)
+    pub truncate_payload: bool,
+    /// Timezone for displaying and entering timestamps
+    pub timezone: Timezone,
+    /// Color theme
+    pub theme: Theme,
+    /// Maximum number of messages indexed per file, from the start of the file
+    pub message_limit: Option<usize>,
+    /// Decoders for the payloads shown in the detail view
+    pub decoders: PayloadDecoders,
+    /// Show a hex dump of the payload in the detail view
+    pub show_hex: bool,
+    /// Whether the detail view shows the raw header bytes
+    pub show_raw_header: bool,
+    /// Pretty-print JSON payloads in the detail view
+    pub pretty_json: bool,
+    /// Show timestamps relative to the anchor message
+    pub relative_timestamps: bool,
+    /// Show the filtered messages newest first, from the bottom of the list up
+    pub reversed: bool,
+    /// Show the index of each message in its file in the first column of the list
+    pub show_line_numbers: bool,
+    /// Anchor for relative timestamps as (file index, message index); the first
+    /// filtered message is used when unset
+    pub time_anchor: Option<(usize, usize)>,
+    /// Start of the marked range (position in the filtered messages); the range
+    /// extends to the selected message
+    pub range_anchor: Option<usize>,
+    /// Message as (file index, message index) that the time to the selected
+    /// message is measured from
+    pub measure_anchor: Option<(usize, usize)>,
+    /// Files (indices into `files`) reported as changed on disk, until re-opened
+    changed_files: HashSet<usize>,
+    /// Follow mode: reload growing files and keep the last message selected
+    pub follow_mode: bool,
+    /// Whether follow mode is actively auto-scrolling (false while paused)
+    pub following: bool,
     /// Should the application exit
     pub should_exit: bool,
 }
 
 impl App {
     /// Create a new application instance
-    pub fn new() -> Self {
-        let filter = FilterCriteria::default();
+    pub fn new(settings: Settings) -> Self {
+        let filter = FilterCriteria {
+            case_insensitive_ids: settings.case_insensitive_ids,
+            ..Default::default()
+        };
         let filter_engine = Some(FilterEngine::new(filter.clone()));
+        let case_sensitive_search = settings.default_case_sensitive;
 
         Self {
+            settings,
+            keymap: KeyMap::default(),
             files: Vec::new(),
             indices: Vec::new(),
             current_file_idx: 0,
+            file_sort: FileSort::Load,
+            file_order: Vec::new(),
+            browser_ratio: 20,
+            browser_visible: None,
+            loading: Vec::new(),
+            indexing_progress: 1.0,
+            file_view_states: HashMap::new(),
+            merged: false,
+            merged_messages: Vec::new(),
             filter,
             filter_engine,
+            errors_only_saved_filter: None,
             filtered_messages: Vec::new(),
+            context_view: None,
             selected_message_idx: 0,
             view_mode: ViewMode::List,
             input_mode: InputMode::Normal,
+            picker: None,
+            histogram: None,
             search_engine: None,
             search_pattern: None,
+            search_query: None,
+            highlights: Vec::new(),
             search_results: Vec::new(),
+            search_result_set: HashSet::new(),
             current_search_idx: 0,
-            case_sensitive_search: true, // Default to case-sensitive search
+            search_origin: 0,
+            incremental_scan: None,
+            case_sensitive_search,
+            search_job: None,
+            next_search_id: 0,
+            export_job: None,
+            next_export_id: 0,
+            events: None,
             command_input: String::new(),
+            history: History::default(),
+            history_pos: None,
+            history_draft: String::new(),
             status_message: String::new(),
+            viewport_height: 0,
+            h_scroll: 0,
+            truncate_payload: true,
+            timezone: Timezone::default(),
+            theme: Theme::default(),
+            message_limit: None,
+            decoders: {
+                // JSON payloads are pretty-printed by the detail view, behind a toggle
+                let mut decoders = PayloadDecoders::new();
+                decoders.register(None, None, Utf8Decoder);
+                decoders
+            },
+            show_hex: false,
+            show_raw_header: false,
+            pretty_json: true,
+            relative_timestamps: false,
+            reversed: false,
+            show_line_numbers: false,
+            time_anchor: None,
+            range_anchor: None,
+            measure_anchor: None,
+            changed_files: HashSet::new(),
+            follow_mode: false,
+            following: false,
             should_exit: false,
         }
     }
 
+    /// Open a DLT file and build its index, covering at most `limit` messages
+    pub fn open_file(
+        path: &Path,
+        cache_size: usize,
+        limit: Option<usize>,
+    ) -> ParserResult<(Arc<DltFile>, Arc<Index>)> {
+        Self::open_file_with_progress(path, cache_size, limit, |_| {})
+    }
+
+    /// Open a DLT file and build its index, reporting the overall progress (0.0 to 1.0)
+    ///
+    /// Scanning the file for messages counts as the first half, indexing
+    /// them as the second half.
+    pub fn open_file_with_progress(
+        path: &Path,
+        cache_size: usize,
+        limit: Option<usize>,
+        progress: impl Fn(f64) + Sync,
+    ) -> ParserResult<(Arc<DltFile>, Arc<Index>)> {
+        let file = Arc::new(DltFile::open_with_limit(path, cache_size, limit, |p| {
+            progress(p / 2.0)
+        })?);
+        let index = Arc::new(Index::with_progress(file.clone(), |p| {
+            progress(0.5 + p / 2.0)
+        })?);
+        Ok((file, index))
+    }
+
     /// Load a DLT file
     pub fn load_file(&mut self, path: PathBuf) -> ParserResult<()> {
-        // Load the file
-        let file = Arc::new(DltFile::open(path)?);
-        let index = Arc::new(Index::new(file.clone())?);
+        let (file, index) =
+            Self::open_file(&path, self.settings.message_cache_size, self.message_limit)?;
+        self.add_file(path, file, index);
+        Ok(())
+    }
+
+    /// Mark a file as being loaded in the background
+    pub fn start_loading(&mut self, path: PathBuf) {
+        self.loading.push((path, 0.0));
+        self.update_indexing_progress();
+    }
+
+    /// Update the progress of a file loaded in the background
+    pub fn update_loading_progress(&mut self, path: &Path, progress: f64) {
+        if let Some(entry) = self.loading.iter_mut().find(|(p, _)| p == path) {
+            entry.1 = progress;
+        }
+        self.update_indexing_progress();
+    }
+
+    /// Recompute the overall progress of the files being loaded
+    fn update_indexing_progress(&mut self) {
+        self.indexing_progress = if self.loading.is_empty() {
+            1.0
+        } else {
+            self.loading.iter().map(|(_, p)| p).sum::<f64>() / self.loading.len() as f64
+        };
+    }
+
+    /// Add a file loaded in the background, or report why it failed to load
+    pub fn finish_loading(
+        &mut self,
+        path: PathBuf,
+        result: ParserResult<(Arc<DltFile>, Arc<Index>)>,
+    ) {
+        self.loading.retain(|(p, _)| p != &path);
+        self.update_indexing_progress();
+
+        match result {
+            Ok((file, index)) => self.add_file(path, file, index),
+            Err(e) => {
+                self.status_message = format!("Error loading file {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    /// Add an opened file to the list of files
+    fn add_file(&mut self, path: PathBuf, file: Arc<DltFile>, index: Arc<Index>) {
+        // Warn about messages skipped because of their protocol version, and
+        // about bytes and messages that couldn't be read
+        let mut skipped: Vec<String> = file
+            .unsupported_versions()
+            .iter()
+            .map(|(version, count)| {
+                format!(
+                    "{} messages with unsupported DLT version {}",
+                    count, version
+                )
+            })
+            .collect();
+        let diagnostics = file.diagnostics();
+        if diagnostics.skipped_regions > 0 {
+            skipped.push(format!(
+                "{} unreadable regions ({} bytes) skipped",
+                diagnostics.skipped_regions, diagnostics.skipped_bytes
+            ));
+        }
+        if !index.unparsable_messages().is_empty() {
+            skipped.push(format!(
+                "{} messages failed to parse",
+                index.unparsable_messages().len()
+            ));
+        }
 
         // Add to the list of files
         self.files.push(file);
         self.indices.push(index);
+        self.sort_files();
+
+        // Remember the file in the recent files list
+        let path = path.canonicalize().unwrap_or(path);
+        self.history
+            .add_recent_file(path, self.settings.max_recent_files);
 
         // Set as the current file if it's the first one
         if self.files.len() == 1 {
             self.current_file_idx = 0;
             self.apply_filter();
+        } else if self.merged {
+            self.build_merged_messages();
+            self.apply_filter();
         }
 
-        Ok(())
+        if !skipped.is_empty() {
+            self.status_message = format!("{} (see :diagnostics)", skipped.join(", "));
+        }
+    }
+
+    /// Build the time-ordered list of messages across all files
+    ///
+    /// Search results are cleared, as they refer to positions in the old list.
+    fn build_merged_messages(&mut self) {
+        self.stop_search();
+        self.clear_search_results();
+
+        let mut entries: Vec<(DateTime<Utc>, usize, usize)> = self
+            .files
+            .iter()
+            .enumerate()
+            .flat_map(|(file_idx, file)| {
+                (0..file.message_count())
+                    .into_par_iter()
+                    .filter_map(|msg_idx| {
+                        file.parse_message(msg_idx)
+                            .ok()
+                            .map(|msg| (msg.timestamp(), file_idx, msg_idx))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        // Sort by timestamp, keeping file order for equal timestamps
+        entries.sort();
+
+        self.merged_messages = entries
+            .into_iter()
+            .map(|(_, file_idx, msg_idx)| (file_idx, msg_idx))
+            .collect();
+    }
+
+    /// Toggle between the per-file view and the merged view
+    pub fn toggle_merged(&mut self) {
+        self.merged = !self.merged;
+
+        if self.merged {
+            self.build_merged_messages();
+        } else {
+            self.merged_messages = Vec::new();
+            self.clear_search_results();
+        }
+
+        self.apply_filter();
+
+        self.status_message = if self.merged {
+            format!("Merged view of {} files", self.files.len())
+        } else {
+            "Per-file view".to_string()
+        };
     }
 
     /// Apply the current filter to the current file
@@ -125,20 +582,129 @@ impl App {
             return;
         }
 
-        let file = &self.files[self.current_file_idx];
-
-        // Apply the filter using the filter engine
-        if let Some(engine) = &self.filter_engine {
+        if self.merged {
+            // Filter the merged messages, keeping positions into the merged list
+            let files = &self.files;
+            let engine = self.filter_engine.as_ref();
+            self.filtered_messages = self
+                .merged_messages
+                .par_iter()
+                .enumerate()
+                .filter_map(|(i, &(file_idx, msg_idx))| {
+                    match files[file_idx].parse_message(msg_idx) {
+                        Ok(msg) if engine.is_none_or(|e| e.matches(&msg)) => Some(i),
+                        _ => None,
+                    }
+                })
+                .collect();
+        } else if let Some(engine) = &self.filter_engine {
+            let file = &self.files[self.current_file_idx];
             self.filtered_messages = engine.apply(file);
         } else {
             // Fallback to direct filtering if no engine is available
+            let file = &self.files[self.current_file_idx];
             self.filtered_messages = (0..file.message_count()).collect();
         }
 
-        // Reset selection
+        // Reset selection, dropping the range and a shown context that refer to the
+        // old positions. Search results refer to messages and are kept, but a running
+        // search only covers the old filtered messages.
         self.selected_message_idx = 0;
-        self.search_results = Vec::new();
-        self.current_search_idx = 0;
+        self.context_view = None;
+        self.range_anchor = None;
+        self.stop_search();
+    }
+
+    /// Show the `radius` messages before and after the selected message in file
+    /// order, regardless of the filter, until `close_context` is called
+    pub fn show_context(&mut self, radius: usize) {
+        let Some(&idx) = self.filtered_messages.get(self.selected_message_idx) else {
+            self.status_message = "No message selected".to_string();
+            return;
+        };
+
+        let total = if self.merged {
+            self.merged_messages.len()
+        } else {
+            self.files[self.current_file_idx].message_count()
+        };
+        let start = idx.saturating_sub(radius);
+        let end = idx.saturating_add(radius).saturating_add(1).min(total);
+
+        // Save the filtered view, unless already showing a context (whose
+        // positions are not filtered)
+        self.stop_search();
+        if let Some(view) = &mut self.context_view {
+            view.radius = radius;
+        } else {
+            self.context_view = Some(ContextView {
+                radius,
+                filtered_messages: std::mem::take(&mut self.filtered_messages),
+                selected_message_idx: self.selected_message_idx,
+                search_results: std::mem::take(&mut self.search_results),
+                current_search_idx: self.current_search_idx,
+                range_anchor: self.range_anchor,
+            });
+        }
+        self.clear_search_results();
+        self.range_anchor = None;
+
+        self.filtered_messages = (start..end).collect();
+        self.selected_message_idx = idx - start;
+        self.status_message = format!(
+            "Showing {} unfiltered messages around the selected one (Esc to return)",
+            self.filtered_messages.len()
+        );
+    }
+
+    /// Return from a shown context to the filtered view, keeping the selected
+    /// message selected if the filter shows it
+    ///
+    /// Returns whether a context was shown.
+    pub fn close_context(&mut self) -> bool {
+        let Some(view) = self.context_view.take() else {
+            return false;
+        };
+
+        let selected = self
+            .filtered_messages
+            .get(self.selected_message_idx)
+            .copied();
+        self.stop_search();
+        self.clear_search_results();
+
+        self.filtered_messages = view.filtered_messages;
+        self.selected_message_idx = selected
+            .and_then(|idx| self.filtered_messages.binary_search(&idx).ok())
+            .unwrap_or(view.selected_message_idx);
+        self.search_result_set.extend(&view.search_results);
+        self.search_results = view.search_results;
+        self.current_search_idx = view.current_search_idx;
+        self.range_anchor = view.range_anchor;
+        self.status_message = format!("Back to {} filtered messages", self.filtered_messages.len());
+        true
+    }
+
+    /// Get the number of messages shown around the selected message, if its
+    /// unfiltered context is shown
+    pub fn context_radius(&self) -> Option<usize> {
+        self.context_view.as_ref().map(|view| view.radius)
+    }
+
+    /// Get the (file index, message index) of a position in the filtered messages
+    pub fn message_location(&self, pos: usize) -> Option<(usize, usize)> {
+        let &idx = self.filtered_messages.get(pos)?;
+        if self.merged {
+            self.merged_messages.get(idx).copied()
+        } else {
+            Some((self.current_file_idx, idx))
+        }
+    }
+
+    /// Get the message at a position in the filtered messages
+    pub fn message_at(&self, pos: usize) -> Option<DltMessage> {
+        let (file_idx, msg_idx) = self.message_location(pos)?;
+        self.files.get(file_idx)?.get_message(msg_idx).ok()
     }
 
     /// Get the currently selected message
@@ -147,13 +713,91 @@ impl App {
             return None;
         }
 
-        let file = &self.files[self.current_file_idx];
-        let idx = self.filtered_messages[self.selected_message_idx];
-        file.get_message(idx).ok()
+        self.message_at(self.selected_message_idx)
+    }
+
+    /// Explain why no message is shown: the files are empty, or the filter hides
+    /// all of their messages
+    pub fn no_messages_text(&self) -> &'static str {
+        let has_messages = if self.merged {
+            !self.merged_messages.is_empty()
+        } else {
+            self.files
+                .get(self.current_file_idx)
+                .is_some_and(|file| file.message_count() > 0)
+        };
+        if has_messages {
+            "No messages match the current filter"
+        } else {
+            "No messages"
+        }
+    }
+
+    /// Get the raw bytes of the selected message as stored in its file
+    pub fn selected_message_bytes(&self) -> Option<&[u8]> {
+        let (file_idx, msg_idx) = self.message_location(self.selected_message_idx)?;
+        self.files.get(file_idx)?.message_bytes(msg_idx).ok()
+    }
+
+    /// Get the byte offset of the selected message in its file
+    pub fn selected_message_offset(&self) -> Option<u64> {
+        let (file_idx, msg_idx) = self.message_location(self.selected_message_idx)?;
+        self.files.get(file_idx)?.message_offset(msg_idx)
+    }
+
+    /// Get the number of messages missing before a position in the filtered messages,
+    /// if the message counter of its ECU skipped values
+    pub fn counter_gap(&self, pos: usize) -> Option<u8> {
+        let (file_idx, msg_idx) = self.message_location(pos)?;
+        self.indices.get(file_idx)?.counter_gap(msg_idx)
+    }
+
+    /// Get the (gap count, dropped message count) of the shown files
+    pub fn counter_gap_totals(&self) -> (usize, usize) {
+        let indices = if self.merged {
+            &self.indices[..]
+        } else {
+            self.indices
+                .get(self.current_file_idx)
+                .map_or(&[][..], std::slice::from_ref)
+        };
+        indices.iter().fold((0, 0), |(gaps, dropped), index| {
+            (
+                gaps + index.counter_gap_count(),
+                dropped + index.dropped_message_count(),
+            )
+        })
+    }
+
+    /// Count the filtered messages matching a pattern, without changing the selection or search
+    pub fn count_matches(&self, pattern: &str) -> AppResult<usize> {
+        let engine = SearchEngine::with_case_sensitivity(pattern, self.case_sensitive_search)?;
+
+        let count = (0..self.filtered_messages.len())
+            .into_par_iter()
+            .filter(|&pos| {
+                self.message_location(pos)
+                    .and_then(|(file_idx, msg_idx)| {
+                        self.files[file_idx].parse_message(msg_idx).ok()
+                    })
+                    .is_some_and(|msg| engine.matches(&msg))
+            })
+            .count();
+
+        Ok(count)
     }
 
     /// Search for a pattern in the filtered messages
-    pub fn search(&mut self, pattern: &str) -> Result<(), regex::Error> {
+    ///
+    /// The search runs in the background; results are added as they are found
+    /// and the first one is selected.
+    pub fn search(&mut self, pattern: &str) -> AppResult<()> {
+        self.start_search(pattern, None)
+    }
+
+    /// Start a search, selecting the message index `keep_selection` once it is
+    /// found (or the first result if it isn't a match)
+    fn start_search(&mut self, pattern: &str, keep_selection: Option<usize>) -> AppResult<()> {
         // Create or update the search engine
         if let Some(engine) = &mut self.search_engine {
             engine.set_pattern_with_case_sensitivity(pattern, self.case_sensitive_search)?;
@@ -171,145 +815,1134 @@ impl App {
             Regex::new(&format!("(?i){}", pattern))?
         };
         self.search_pattern = Some(regex);
+        self.search_query = Some(pattern.to_string());
 
-        // Find matches
-        self.search_results = Vec::new();
+        // Stop any running search and clear its results
+        self.stop_search();
+        self.clear_search_results();
 
         if self.files.is_empty() || self.filtered_messages.is_empty() {
             return Ok(());
         }
 
-        let file = &self.files[self.current_file_idx];
-        let engine = self.search_engine.as_ref().unwrap();
+        // Snapshot what the search needs, so it can run on another thread
+        let engine = self.search_engine.clone().unwrap();
+        let files = self.files.clone();
+        let locations: Vec<Option<(usize, usize)>> = (0..self.filtered_messages.len())
+            .map(|pos| self.message_location(pos))
+            .collect();
+        let indices = self.filtered_messages.clone();
+
+        let id = self.next_search_id;
+        self.next_search_id += 1;
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.search_job = Some(SearchJob {
+            id,
+            cancel: cancel.clone(),
+            progress: 0.0,
+            pattern: pattern.to_string(),
+            keep_selection,
+            selection_pending: true,
+        });
+
+        // Without an event loop to report to, search synchronously
+        let Some(events) = self.events.clone() else {
+            let results = search_chunk(&engine, &files, &locations, &indices);
+            self.add_search_results(id, results, 1.0);
+            self.finish_search(id);
+            return Ok(());
+        };
+
+        self.status_message = format!("Searching for '{}'…", pattern);
+        thread::spawn(move || {
+            let chunks = locations
+                .chunks(SEARCH_CHUNK_SIZE)
+                .zip(indices.chunks(SEARCH_CHUNK_SIZE));
+            for (i, (chunk, chunk_indices)) in chunks.enumerate() {
+                if cancel.load(Ordering::Relaxed) {
+                    return;
+                }
 
-        // Use the search engine to find matches
-        for (i, &idx) in self.filtered_messages.iter().enumerate() {
-            if let Ok(msg) = file.get_message(idx) {
-                if engine.matches(&msg) {
-                    self.search_results.push(i);
+                let results = search_chunk(&engine, &files, chunk, chunk_indices);
+                let searched = i * SEARCH_CHUNK_SIZE + chunk.len();
+                let progress = searched as f64 / locations.len() as f64;
+                if events
+                    .send(Event::SearchProgress(id, results, progress))
+                    .is_err()
+                {
+                    return;
                 }
             }
+            let _ = events.send(Event::SearchFinished(id));
+        });
+
+        Ok(())
+    }
+
+    /// Add results found by a background search
+    ///
+    /// The results are message indices, kept in ascending order, which `n`/`N`
+    /// and the binary search for the selection rely on: chunks cover ascending
+    /// ranges of filtered messages and arrive in order, and each chunk is sorted
+    /// here.
+    pub fn add_search_results(&mut self, id: u64, mut results: Vec<usize>, progress: f64) {
+        let Some(job) = self.search_job.as_mut().filter(|job| job.id == id) else {
+            return;
+        };
+        job.progress = progress;
+
+        results.sort_unstable();
+        let first_new = self.search_results.len();
+        self.search_result_set.extend(&results);
+        self.search_results.extend(results);
+
+        // Select the wanted result as soon as it is found
+        if job.selection_pending {
+            let found = match job.keep_selection {
+                Some(selected) => self.search_results[first_new..]
+                    .binary_search(&selected)
+                    .ok()
+                    .map(|pos| first_new + pos),
+                None => (first_new < self.search_results.len()).then_some(0),
+            };
+            if let Some(pos) = found {
+                job.selection_pending = false;
+                self.select_search_result(pos);
+            }
+        }
+    }
+
+    /// Finish a background search
+    pub fn finish_search(&mut self, id: u64) {
+        let Some(job) = self.search_job.take_if(|job| job.id == id) else {
+            return;
+        };
+
+        // Fall back to the first result if the wanted one wasn't a match
+        if job.selection_pending && !self.search_results.is_empty() {
+            self.select_search_result(0);
         }
 
         // Update status message
         if self.search_results.is_empty() {
-            self.status_message = format!("No matches found for '{}'", pattern);
+            self.status_message = format!("No matches found for '{}'", job.pattern);
         } else {
             self.status_message = format!(
                 "Found {} matches for '{}'",
                 self.search_results.len(),
-                pattern
+                job.pattern
+            );
+        }
+    }
+
+    /// Cancel the background search, keeping the results found so far
+    pub fn cancel_search(&mut self) {
+        if self.stop_search() {
+            self.status_message = format!(
+                "Search cancelled after {} matches",
+                self.search_results.len()
             );
         }
+    }
+
+    /// Stop the background search, returning whether one was running
+    fn stop_search(&mut self) -> bool {
+        match self.search_job.take() {
+            Some(job) => {
+                job.cancel.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
 
-        // Reset search index
+    /// Clear the search results and their current index
+    fn clear_search_results(&mut self) {
+        self.search_results.clear();
+        self.search_result_set.clear();
         self.current_search_idx = 0;
+    }
+
+    /// Check whether a position in the filtered messages is a search result
+    pub fn is_search_result(&self, pos: usize) -> bool {
+        self.filtered_messages
+            .get(pos)
+            .is_some_and(|idx| self.search_result_set.contains(idx))
+    }
 
-        // Select the first result if any
-        if !self.search_results.is_empty() {
-            self.selected_message_idx = self.search_results[0];
+    /// Get the position of the current search result, if there are results and
+    /// the filter shows it
+    pub fn current_search_result(&self) -> Option<usize> {
+        let &idx = self.search_results.get(self.current_search_idx)?;
+        self.filtered_position(idx)
+    }
+
+    /// Get the positions in the filtered messages of the search results the
+    /// filter shows
+    pub fn search_result_positions(&self) -> Vec<usize> {
+        self.search_results
+            .iter()
+            .filter_map(|&idx| self.filtered_position(idx))
+            .collect()
+    }
+
+    /// Get the position of a message index in the filtered messages, if shown
+    fn filtered_position(&self, idx: usize) -> Option<usize> {
+        self.filtered_messages.binary_search(&idx).ok()
+    }
+
+    /// Make a search result the current one, selecting it if the filter shows it
+    fn select_search_result(&mut self, result: usize) {
+        self.current_search_idx = result;
+        if let Some(pos) = self.filtered_position(self.search_results[result]) {
+            self.selected_message_idx = pos;
         }
+    }
 
-        Ok(())
+    /// Check whether a search is running in the background
+    pub fn is_searching(&self) -> bool {
+        self.search_job.is_some()
     }
 
-    /// Move to the next search result
-    pub fn next_search_result(&mut self) {
-        if self.search_results.is_empty() {
+    /// Get the progress (0.0 to 1.0) of the background search, if one is running
+    pub fn search_progress(&self) -> Option<f64> {
+        self.search_job.as_ref().map(|job| job.progress)
+    }
+
+    /// Export the marked range, or all filtered messages, to a file in the background
+    ///
+    /// The format is chosen by the file extension (.json for JSON).
+    pub fn start_export(&mut self, path: &Path) {
+        if self.export_job.is_some() {
+            self.status_message = "An export is already running".to_string();
+            return;
+        }
+        if self.filtered_messages.is_empty() {
+            self.status_message = "Nothing to export".to_string();
+            return;
+        }
+
+        // Create the file up front, so errors show immediately
+        let file = match File::create(path) {
+            Ok(file) => file,
+            Err(e) => {
+                self.status_message = format!("Error writing {}: {}", path.display(), e);
+                return;
+            }
+        };
+
+        // Snapshot what the export needs, so it can run on another thread
+        let range = self
+            .marked_range()
+            .unwrap_or(0..=self.filtered_messages.len() - 1);
+        let locations: Vec<Option<(usize, usize)>> =
+            range.map(|pos| self.message_location(pos)).collect();
+        let files = self.files.clone();
+        let format = OutputFormat::from_path(path);
+        let timezone = self.timezone;
+        let timestamp_format = self.settings.list_timestamp_format.clone();
+
+        let id = self.next_export_id;
+        self.next_export_id += 1;
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.export_job = Some(ExportJob {
+            id,
+            cancel: cancel.clone(),
+            path: path.to_path_buf(),
+            written: 0,
+            total: locations.len(),
+        });
+
+        // Without an event loop to report to, export synchronously
+        let Some(events) = self.events.clone() else {
+            let result = export_chunks(
+                file,
+                &files,
+                &locations,
+                format,
+                timezone,
+                &timestamp_format,
+                |_| true,
+            );
+            self.finish_export(id, result);
             return;
+        };
+
+        thread::spawn(move || {
+            let report = |written| {
+                !cancel.load(Ordering::Relaxed)
+                    && events.send(Event::ExportProgress(id, written)).is_ok()
+            };
+            let result = export_chunks(
+                file,
+                &files,
+                &locations,
+                format,
+                timezone,
+                &timestamp_format,
+                report,
+            );
+
+            // A cancelled export was already cleaned up
+            if !cancel.load(Ordering::Relaxed) {
+                let _ = events.send(Event::ExportFinished(id, result));
+            }
+        });
+    }
+
+    /// Update the number of messages written by a background export
+    pub fn update_export_progress(&mut self, id: u64, written: usize) {
+        if let Some(job) = self.export_job.as_mut().filter(|job| job.id == id) {
+            job.written = written;
         }
+    }
+
+    /// Finish a background export, removing the partial file if it failed
+    pub fn finish_export(&mut self, id: u64, result: io::Result<usize>) {
+        let Some(job) = self.export_job.take_if(|job| job.id == id) else {
+            return;
+        };
+
+        self.status_message = match result {
+            Ok(count) => format!("Exported {} messages to {}", count, job.path.display()),
+            Err(e) => {
+                let _ = fs::remove_file(&job.path);
+                format!("Error writing {}: {}", job.path.display(), e)
+            }
+        };
+    }
 
-        self.current_search_idx = (self.current_search_idx + 1) % self.search_results.len();
-        self.selected_message_idx = self.search_results[self.current_search_idx];
+    /// Cancel the background export and remove its partial file
+    pub fn cancel_export(&mut self) {
+        let Some(job) = self.export_job.take() else {
+            return;
+        };
+
+        job.cancel.store(true, Ordering::Relaxed);
+        let _ = fs::remove_file(&job.path);
+        self.status_message = format!("Export to {} cancelled", job.path.display());
+    }
+
+    /// Check whether an export is running in the background
+    pub fn is_exporting(&self) -> bool {
+        self.export_job.is_some()
+    }
+
+    /// Get the (written, total) message counts of the background export, if one is running
+    pub fn export_progress(&self) -> Option<(usize, usize)> {
+        self.export_job.as_ref().map(|job| (job.written, job.total))
+    }
+
+    /// Move to the next search result shown by the filter
+    pub fn next_search_result(&mut self) {
+        let count = self.search_results.len();
+        let next = (1..=count)
+            .map(|step| self.current_search_idx + step)
+            .find(|&i| {
+                self.filtered_position(self.search_results[i % count])
+                    .is_some()
+            });
+        let Some(next) = next else {
+            self.status_message = self.no_search_results_message();
+            return;
+        };
+
+        self.select_search_result(next % count);
+        self.search_status_message(next >= count, "Search wrapped to top");
     }
 
-    /// Move to the previous search result
+    /// Move to the previous search result shown by the filter
     pub fn prev_search_result(&mut self) {
-        if self.search_results.is_empty() {
+        let count = self.search_results.len();
+        let prev = (1..=count)
+            .map(|step| self.current_search_idx + count - step)
+            .find(|&i| {
+                self.filtered_position(self.search_results[i % count])
+                    .is_some()
+            });
+        let Some(prev) = prev else {
+            self.status_message = self.no_search_results_message();
             return;
+        };
+
+        self.select_search_result(prev % count);
+        self.search_status_message(prev < count, "Search wrapped to bottom");
+    }
+
+    /// Get the message for `n`/`N` finding no result to move to
+    fn no_search_results_message(&self) -> String {
+        if self.search_results.is_empty() {
+            "No matches".to_string()
+        } else {
+            format!(
+                "None of the {} matches is shown by the filter",
+                self.search_results.len()
+            )
         }
+    }
 
-        self.current_search_idx = if self.current_search_idx == 0 {
-            self.search_results.len() - 1
+    /// Show the position among the search results, noting when the search wrapped
+    fn search_status_message(&mut self, wrapped: bool, wrap_message: &str) {
+        let position = format!(
+            "Match {}/{}",
+            self.current_search_idx + 1,
+            self.search_results.len()
+        );
+        self.status_message = if wrapped {
+            format!("{} ({})", wrap_message, position)
         } else {
-            self.current_search_idx - 1
+            position
         };
-
-        self.selected_message_idx = self.search_results[self.current_search_idx];
     }
 
     /// Move the selection up
     pub fn move_up(&mut self) {
-        if self.selected_message_idx > 0 {
-            self.selected_message_idx -= 1;
+        if self.reversed {
+            self.select_later(1);
+        } else {
+            self.select_earlier(1);
         }
     }
 
     /// Move the selection down
     pub fn move_down(&mut self) {
-        if !self.filtered_messages.is_empty()
-            && self.selected_message_idx < self.filtered_messages.len() - 1
-        {
-            self.selected_message_idx += 1;
+        if self.reversed {
+            self.select_earlier(1);
+        } else {
+            self.select_later(1);
         }
     }
 
-    /// Move the selection to the top
-    pub fn move_to_top(&mut self) {
-        self.selected_message_idx = 0;
+    /// Move the selection up by half the viewport height
+    pub fn half_page_up(&mut self) {
+        if self.reversed {
+            self.select_later(self.half_page());
+        } else {
+            self.select_earlier(self.half_page());
+        }
     }
 
-    /// Move the selection to the bottom
-    pub fn move_to_bottom(&mut self) {
-        if !self.filtered_messages.is_empty() {
-            self.selected_message_idx = self.filtered_messages.len() - 1;
+    /// Move the selection down by half the viewport height
+    pub fn half_page_down(&mut self) {
+        if self.reversed {
+            self.select_earlier(self.half_page());
+        } else {
+            self.select_later(self.half_page());
         }
     }
 
-    /// Switch to the next file
-    pub fn next_file(&mut self) {
-        if self.files.len() > 1 {
-            self.current_file_idx = (self.current_file_idx + 1) % self.files.len();
-            self.apply_filter();
-        }
+    /// Move the selection back by a number of filtered messages
+    fn select_earlier(&mut self, count: usize) {
+        self.pause_following();
+        self.selected_message_idx = self.selected_message_idx.saturating_sub(count);
     }
 
-    /// Switch to the previous file
-    pub fn prev_file(&mut self) {
-        if self.files.len() > 1 {
-            self.current_file_idx = if self.current_file_idx == 0 {
-                self.files.len() - 1
-            } else {
-                self.current_file_idx - 1
-            };
-            self.apply_filter();
+    /// Move the selection forward by a number of filtered messages
+    fn select_later(&mut self, count: usize) {
+        if !self.filtered_messages.is_empty() {
+            self.selected_message_idx =
+                (self.selected_message_idx + count).min(self.filtered_messages.len() - 1);
         }
     }
 
-    /// Toggle the view mode between list and detail
-    pub fn toggle_view_mode(&mut self) {
-        self.view_mode = match self.view_mode {
-            ViewMode::List => ViewMode::Detail,
-            ViewMode::Detail => ViewMode::List,
-            ViewMode::Help => ViewMode::List,
-        };
+    /// Set the height of the log list, e.g. after the terminal was resized
+    ///
+    /// The list scrolls to keep the selection visible, so only the selection
+    /// itself needs to be kept within the filtered messages.
+    pub fn set_viewport_height(&mut self, height: usize) {
+        self.viewport_height = height;
+        self.selected_message_idx = self
+            .selected_message_idx
+            .min(self.filtered_messages.len().saturating_sub(1));
     }
 
-    /// Show the help view
-    pub fn show_help(&mut self) {
-        self.view_mode = ViewMode::Help;
+    /// Get the number of messages in half the viewport (at least one)
+    fn half_page(&self) -> usize {
+        (self.viewport_height / 2).max(1)
     }
 
-    /// Enter search mode
-    pub fn enter_search_mode(&mut self) {
-        self.input_mode = InputMode::Search;
-        self.command_input = String::new();
-        self.status_message = "Search: ".to_string();
+    /// Convert between a position in the filtered messages and the row of the
+    /// list showing it, counted from the top of the whole list
+    ///
+    /// The conversion is the same in both directions.
+    pub fn display_index(&self, idx: usize) -> usize {
+        if self.reversed {
+            self.filtered_messages.len().saturating_sub(idx + 1)
+        } else {
+            idx
+        }
     }
 
-    /// Exit search mode
-    pub fn exit_search_mode(&mut self) {
+    /// Get the row (see [`App::display_index`]) of the first visible message in a
+    /// list of the given height
+    pub fn scroll_offset(&self, height: usize) -> usize {
+        (self.display_index(self.selected_message_idx) + 1).saturating_sub(height)
+    }
+
+    /// Select the message shown on a row of a list of the given height
+    pub fn select_visible_row(&mut self, row: usize, height: usize) {
+        let row = self.scroll_offset(height) + row;
+        if row < self.filtered_messages.len() {
+            let idx = self.display_index(row);
+            if idx < self.selected_message_idx {
+                self.pause_following();
+            }
+            self.selected_message_idx = idx;
+        }
+    }
+
+    /// Move the selection to the top
+    pub fn move_to_top(&mut self) {
+        if self.reversed {
+            self.select_last();
+        } else {
+            self.select_first();
+        }
+    }
+
+    /// Move the selection to the bottom
+    pub fn move_to_bottom(&mut self) {
+        if self.reversed {
+            self.select_first();
+        } else {
+            self.select_last();
+        }
+    }
+
+    /// Select the first filtered message
+    fn select_first(&mut self) {
+        self.pause_following();
+        self.selected_message_idx = 0;
+    }
+
+    /// Select the last filtered message
+    fn select_last(&mut self) {
+        // Going to the newest message resumes a paused follow mode
+        if self.follow_mode {
+            self.following = true;
+        }
+        if !self.filtered_messages.is_empty() {
+            self.selected_message_idx = self.filtered_messages.len() - 1;
+        }
+    }
+
+    /// Toggle follow mode
+    pub fn toggle_follow_mode(&mut self) {
+        self.follow_mode = !self.follow_mode;
+        self.following = self.follow_mode;
+
+        if self.follow_mode {
+            self.select_last();
+            self.status_message = if self.files.iter().any(|file| file.is_limited()) {
+                "Following (files cut at the message limit don't grow)".to_string()
+            } else if self.reversed {
+                "Following (scroll down to pause, g to resume)".to_string()
+            } else {
+                "Following (scroll up to pause, G to resume)".to_string()
+            };
+        } else {
+            self.status_message = "Stopped following".to_string();
+        }
+    }
+
+    /// Pause auto-scrolling after manual upward navigation
+    fn pause_following(&mut self) {
+        self.following = false;
+    }
+
+    /// Select the message at a 1-based position in the filtered messages, clamped to the list
+    pub fn goto_message(&mut self, number: usize) {
+        if self.filtered_messages.is_empty() {
+            return;
+        }
+
+        self.pause_following();
+        self.keep_selection_during_search();
+        self.selected_message_idx = number
+            .saturating_sub(1)
+            .min(self.filtered_messages.len() - 1);
+        self.status_message = format!("Jumped to message {}", self.selected_message_idx + 1);
+    }
+
+    /// Select the first filtered message at or after a time of day (`HH:MM[:SS[.fff]]`)
+    pub fn goto_time(&mut self, value: &str) -> FilterResult<()> {
+        let time = parse_time_of_day(value, self.reference_date(), self.timezone)
+            .ok_or_else(|| FilterError::Time(value.to_string()))?;
+
+        if self.filtered_messages.is_empty() {
+            return Ok(());
+        }
+
+        // Messages aren't necessarily in time order, so find the first one in list order
+        let pos = (0..self.filtered_messages.len())
+            .into_par_iter()
+            .find_first(|&pos| {
+                self.message_location(pos)
+                    .and_then(|(file_idx, msg_idx)| {
+                        self.files[file_idx].parse_message(msg_idx).ok()
+                    })
+                    .is_some_and(|msg| msg.timestamp() >= time)
+            });
+
+        self.pause_following();
+        self.keep_selection_during_search();
+        match pos {
+            Some(pos) => {
+                self.selected_message_idx = pos;
+                self.status_message = format!("Jumped to {}", value);
+            }
+            None => {
+                self.selected_message_idx = self.filtered_messages.len() - 1;
+                self.status_message = format!("No messages at or after {}", value);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Keep the current selection when a running search finds its first result
+    fn keep_selection_during_search(&mut self) {
+        if let Some(job) = &mut self.search_job {
+            job.selection_pending = false;
+        }
+    }
+
+    /// Reload files that have grown since they were opened while in follow mode
+    ///
+    /// Returns whether anything changed that needs to be redrawn.
+    pub fn poll_follow(&mut self) -> bool {
+        if !self.follow_mode {
+            return false;
+        }
+
+        let mut changed = false;
+        let mut failed = false;
+        for i in 0..self.files.len() {
+            // Files already cut at the message limit can't show new messages
+            if self.files[i].is_limited() {
+                continue;
+            }
+
+            let grown = fs::metadata(self.files[i].path())
+                .map(|m| m.len() > self.files[i].size())
+                .unwrap_or(false);
+            if !grown {
+                continue;
+            }
+
+            if self.reopen_file(i) {
+                changed = true;
+            } else {
+                failed = true;
+            }
+        }
+
+        if !changed {
+            return failed;
+        }
+
+        self.files_reopened();
+        true
+    }
+
+    /// Check whether the files changed on disk since they were opened
+    ///
+    /// Truncated files are re-opened right away, since reading their mapped data
+    /// past the new end would crash. Other changes are reported once, to be picked
+    /// up with `:reload` (or follow mode, for growing files).
+    ///
+    /// Returns whether anything changed that needs to be redrawn.
+    pub fn check_file_changes(&mut self) -> bool {
+        let mut changed = false;
+        let mut reopened = false;
+        for i in 0..self.files.len() {
+            let Some(change) = self.files[i].check_changed() else {
+                continue;
+            };
+            let name = self.files[i]
+                .path()
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+
+            match change {
+                // Follow mode picks up growing files itself
+                FileChange::Grown if self.follow_mode && !self.files[i].is_limited() => {}
+                FileChange::Truncated => {
+                    if self.reopen_file(i) {
+                        self.status_message =
+                            format!("{} was truncated on disk and re-opened", name);
+                        reopened = true;
+                    }
+                    changed = true;
+                }
+                _ if self.changed_files.insert(i) => {
+                    self.status_message = format!("{} changed on disk (:reload to re-open)", name);
+                    changed = true;
+                }
+                _ => {}
+            }
+        }
+
+        if reopened {
+            // Messages may have moved, so the search results no longer apply
+            self.clear_search_results();
+            self.files_reopened();
+        }
+        changed
+    }
+
+    /// Re-open all files from disk, e.g. after they changed
+    pub fn reload_files(&mut self) {
+        let reopened = (0..self.files.len())
+            .filter(|&i| self.reopen_file(i))
+            .count();
+        if reopened == 0 {
+            return;
+        }
+
+        // Messages may have moved, so the search results no longer apply
+        self.clear_search_results();
+        self.files_reopened();
+        if reopened == self.files.len() {
+            self.status_message = format!("Re-opened {} file(s)", reopened);
+        }
+    }
+
+    /// Re-open a file from disk, reporting errors in the status message
+    ///
+    /// Returns whether the file was re-opened; `files_reopened` updates the view.
+    fn reopen_file(&mut self, i: usize) -> bool {
+        let path = self.files[i].path().to_path_buf();
+        match Self::open_file(&path, self.settings.message_cache_size, self.message_limit) {
+            Ok((file, index)) => {
+                self.files[i] = file;
+                self.indices[i] = index;
+                self.changed_files.remove(&i);
+                true
+            }
+            Err(e) => {
+                self.status_message = format!("Error reloading {}: {}", path.display(), e);
+                false
+            }
+        }
+    }
+
+    /// Update the view after files were re-opened
+    ///
+    /// The files are re-sorted, as they changed, and re-filtered, keeping the
+    /// selection in place unless following.
+    fn files_reopened(&mut self) {
+        self.sort_files();
+        let selected = self.selected_message_idx;
+        if self.merged {
+            self.build_merged_messages();
+        }
+        self.apply_filter();
+
+        if self.following {
+            self.select_last();
+        } else if !self.filtered_messages.is_empty() {
+            self.selected_message_idx = selected.min(self.filtered_messages.len() - 1);
+        }
+    }
+
+    /// Switch to the next file
+    pub fn next_file(&mut self) {
+        if self.files.len() > 1 && !self.merged {
+            let pos = self.file_order_position();
+            self.switch_file(self.file_order[(pos + 1) % self.file_order.len()]);
+        }
+    }
+
+    /// Switch to the previous file
+    pub fn prev_file(&mut self) {
+        if self.files.len() > 1 && !self.merged {
+            let pos = self.file_order_position();
+            let pos = if pos == 0 {
+                self.file_order.len() - 1
+            } else {
+                pos - 1
+            };
+            self.switch_file(self.file_order[pos]);
+        }
+    }
+
+    /// Get the position of the current file in the file browser
+    fn file_order_position(&self) -> usize {
+        self.file_order
+            .iter()
+            .position(|&idx| idx == self.current_file_idx)
+            .unwrap_or(0)
+    }
+
+    /// Set the order of the files in the file browser
+    pub fn set_file_sort(&mut self, sort: FileSort) {
+        self.file_sort = sort;
+        self.sort_files();
+        self.status_message = format!("Sorting files by {}", sort.name());
+    }
+
+    /// Widen the file browser in the list view
+    pub fn widen_browser(&mut self) {
+        self.resize_browser(self.browser_ratio.saturating_add(BROWSER_RESIZE_STEP));
+    }
+
+    /// Narrow the file browser in the list view
+    pub fn narrow_browser(&mut self) {
+        self.resize_browser(self.browser_ratio.saturating_sub(BROWSER_RESIZE_STEP));
+    }
+
+    /// Check whether the file browser is shown in the list view
+    pub fn is_browser_visible(&self) -> bool {
+        self.browser_visible.unwrap_or(self.files.len() != 1)
+    }
+
+    /// Set the width of the file browser, showing it if it was hidden
+    fn resize_browser(&mut self, ratio: u16) {
+        let (min, max) = BROWSER_RATIO_RANGE;
+        self.browser_ratio = ratio.clamp(min, max);
+        self.browser_visible = Some(true);
+        self.status_message = format!("File browser width: {}%", self.browser_ratio);
+    }
+
+    /// Show or hide the file browser in the list view
+    pub fn toggle_browser(&mut self) {
+        let visible = !self.is_browser_visible();
+        self.browser_visible = Some(visible);
+        self.status_message = if visible {
+            "Showing the file browser".to_string()
+        } else {
+            "Hiding the file browser".to_string()
+        };
+    }
+
+    /// Recompute the order of the files in the file browser
+    ///
+    /// The sort is stable, so files that compare equal stay in load order.
+    fn sort_files(&mut self) {
+        let files = &self.files;
+        let mut order: Vec<usize> = (0..files.len()).collect();
+        match self.file_sort {
+            FileSort::Load => {}
+            FileSort::Name => order.sort_by_key(|&idx| files[idx].path().file_name()),
+            FileSort::Modified => order.sort_by_cached_key(|&idx| {
+                fs::metadata(files[idx].path())
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+            }),
+            FileSort::Messages => order.sort_by_key(|&idx| files[idx].message_count()),
+        }
+        self.file_order = order;
+    }
+
+    /// Switch to another file, remembering the view state of the current one
+    fn switch_file(&mut self, idx: usize) {
+        // Remember where we were in the current file
+        if let Some(&message_idx) = self.filtered_messages.get(self.selected_message_idx) {
+            self.file_view_states.insert(
+                self.current_file_idx,
+                FileViewState {
+                    message_idx,
+                    h_scroll: self.h_scroll,
+                },
+            );
+        }
+
+        // Search results refer to the messages of the previous file
+        self.current_file_idx = idx;
+        self.clear_search_results();
+        self.apply_filter();
+        self.restore_file_view();
+    }
+
+    /// Restore the view state of the current file, selecting the nearest shown message
+    fn restore_file_view(&mut self) {
+        match self.file_view_states.get(&self.current_file_idx) {
+            Some(state) if !self.filtered_messages.is_empty() => {
+                let pos = if self.merged {
+                    let location = (self.current_file_idx, state.message_idx);
+                    self.filtered_messages
+                        .iter()
+                        .position(|&i| self.merged_messages.get(i) == Some(&location))
+                        .unwrap_or(0)
+                } else {
+                    self.filtered_messages
+                        .partition_point(|&i| i < state.message_idx)
+                };
+                self.selected_message_idx = pos.min(self.filtered_messages.len() - 1);
+                self.h_scroll = state.h_scroll;
+            }
+            _ => self.h_scroll = 0,
+        }
+    }
+
+    /// Get the open files, selections and filter, to be restored in a later session
+    pub fn session(&self) -> Session {
+        let selected = self.message_location(self.selected_message_idx);
+        let files = self
+            .files
+            .iter()
+            .enumerate()
+            .map(|(file_idx, file)| SessionFile {
+                path: file
+                    .path()
+                    .canonicalize()
+                    .unwrap_or_else(|_| file.path().to_path_buf()),
+                message_idx: match selected {
+                    Some((idx, message_idx)) if idx == file_idx => Some(message_idx),
+                    _ => self
+                        .file_view_states
+                        .get(&file_idx)
+                        .map(|state| state.message_idx),
+                },
+            })
+            .collect::<Vec<_>>();
+
+        // In the merged view, the current file is the one of the selected message
+        let current_file = if self.merged {
+            selected.map(|(file_idx, _)| file_idx)
+        } else {
+            Some(self.current_file_idx)
+        };
+
+        Session {
+            current_file: current_file
+                .and_then(|idx| files.get(idx))
+                .map(|file| file.path.clone()),
+            merged: self.merged,
+            filter: FilterCriteriaConfig::from(&self.filter),
+            files,
+        }
+    }
+
+    /// Restore the filter, view and selections of an earlier session
+    ///
+    /// Files of the session that aren't open are skipped.
+    pub fn restore_session(&mut self, session: &Session) {
+        let file_idx =
+            |files: &[Arc<DltFile>], path: &Path| files.iter().position(|file| file.path() == path);
+
+        // Remember the selection of each file
+        for entry in &session.files {
+            if let (Some(idx), Some(message_idx)) =
+                (file_idx(&self.files, &entry.path), entry.message_idx)
+            {
+                self.file_view_states.insert(
+                    idx,
+                    FileViewState {
+                        message_idx,
+                        h_scroll: 0,
+                    },
+                );
+            }
+        }
+
+        if let Some(idx) = session
+            .current_file
+            .as_deref()
+            .and_then(|path| file_idx(&self.files, path))
+        {
+            self.current_file_idx = idx;
+        }
+        if session.merged && self.files.len() > 1 && !self.merged {
+            self.merged = true;
+            self.build_merged_messages();
+        }
+
+        // Apply the filter, then select the remembered message
+        self.status_message = match FilterCriteria::try_from(session.filter.clone()) {
+            Ok(criteria) => {
+                self.filter = criteria;
+                format!("Resumed session with {} files", self.files.len())
+            }
+            Err(e) => format!("Ignoring invalid filter of the last session: {}", e),
+        };
+        self.update_filter();
+        self.restore_file_view();
+    }
+
+    /// Toggle the view mode between list and detail
+    pub fn toggle_view_mode(&mut self) {
+        self.view_mode = match self.view_mode {
+            ViewMode::List => ViewMode::Detail,
+            ViewMode::Detail => ViewMode::List,
+            ViewMode::Help => ViewMode::List,
+            ViewMode::Picker => ViewMode::List,
+            ViewMode::Histogram => ViewMode::List,
+        };
+    }
+
+    /// Open the histogram of the filtered messages over time
+    pub fn open_histogram(&mut self) {
+        // Look up the timestamps of all filtered messages
+        let files = &self.files;
+        let merged = &self.merged_messages;
+        let (is_merged, current_file_idx) = (self.merged, self.current_file_idx);
+        let timestamps: Vec<DateTime<Utc>> = self
+            .filtered_messages
+            .par_iter()
+            .map(|&idx| {
+                let (file_idx, msg_idx) = if is_merged {
+                    merged[idx]
+                } else {
+                    (current_file_idx, idx)
+                };
+                files[file_idx]
+                    .parse_message(msg_idx)
+                    .map(|msg| msg.timestamp())
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let Some(mut histogram) = Histogram::new(timestamps, HISTOGRAM_BINS) else {
+            self.status_message = "No messages to show in the histogram".to_string();
+            return;
+        };
+
+        // Start at the bin of the selected message
+        if let Some(msg) = self.selected_message() {
+            histogram.selected = histogram.bin_of(msg.timestamp());
+        }
+        self.histogram = Some(histogram);
+        self.view_mode = ViewMode::Histogram;
+    }
+
+    /// Close the histogram
+    pub fn close_histogram(&mut self) {
+        self.histogram = None;
+        self.view_mode = ViewMode::List;
+    }
+
+    /// Select the first message of the selected histogram bin and close the histogram
+    pub fn histogram_jump(&mut self) {
+        let Some(histogram) = &self.histogram else {
+            return;
+        };
+
+        match histogram.first_position(histogram.selected) {
+            Some(pos) => {
+                self.pause_following();
+                self.selected_message_idx = pos;
+                self.close_histogram();
+            }
+            None => self.status_message = "No messages in this period".to_string(),
+        }
+    }
+
+    /// Open the file picker in the current directory
+    pub fn open_picker(&mut self) {
+        let dir = self.settings.work_dir();
+        self.picker = Some(FilePicker::new(dir, &self.history.recent_files));
+        self.view_mode = ViewMode::Picker;
+    }
+
+    /// Close the file picker
+    pub fn close_picker(&mut self) {
+        self.picker = None;
+        self.view_mode = ViewMode::List;
+    }
+
+    /// Open the selected picker entry (a file is loaded, a directory is browsed)
+    pub fn picker_open_selected(&mut self) {
+        let Some(picker) = &mut self.picker else {
+            return;
+        };
+
+        match picker.selected_entry().cloned() {
+            Some(PickerEntry::Recent(path)) | Some(PickerEntry::File(path)) => {
+                match self.load_file(path.clone()) {
+                    Ok(()) => {
+                        // Show the newly opened file
+                        if !self.merged {
+                            self.switch_file(self.files.len() - 1);
+                        }
+                        self.close_picker();
+                        self.status_message = format!("Opened {}", path.display());
+                    }
+                    Err(e) => {
+                        self.status_message =
+                            format!("Error loading file {}: {}", path.display(), e);
+                    }
+                }
+            }
+            Some(PickerEntry::Directory(dir)) => picker.change_dir(dir),
+            Some(PickerEntry::Parent) => picker.parent_dir(),
+            None => {}
+        }
+    }
+
+    /// Scroll the payloads in the list left
+    pub fn scroll_left(&mut self) {
+        self.h_scroll = self.h_scroll.saturating_sub(H_SCROLL_STEP);
+    }
+
+    /// Scroll the payloads in the list right, up to the end of the longest visible line
+    pub fn scroll_right(&mut self) {
+        let max_scroll = self.longest_visible_line().saturating_sub(1);
+        self.h_scroll = (self.h_scroll + H_SCROLL_STEP).min(max_scroll);
+    }
+
+    /// Scroll the payloads in the list back to their start
+    pub fn scroll_to_start(&mut self) {
+        self.h_scroll = 0;
+    }
+
+    /// Scroll the payloads in the list to the end of the longest visible line
+    pub fn scroll_to_end(&mut self) {
+        self.h_scroll = self.longest_visible_line().saturating_sub(1);
+    }
+
+    /// Get the length in characters of the longest payload visible in the list, as
+    /// shown on its row
+    fn longest_visible_line(&self) -> usize {
+        let start = self.scroll_offset(self.viewport_height);
+        let end = (start + self.viewport_height).min(self.filtered_messages.len());
+
+        (start..end)
+            .filter_map(|row| self.message_at(self.display_index(row)))
+            .map(|msg| {
+                msg.payload_line(
+                    self.settings.max_payload_display,
+                    self.settings.strip_ansi,
+                    None,
+                )
+                .chars()
+                .count()
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Toggle between truncating and clipping long payloads in the list
+    pub fn toggle_truncate_payload(&mut self) {
+        self.truncate_payload = !self.truncate_payload;
+        self.status_message = if self.truncate_payload {
+            "Truncating long payloads".to_string()
+        } else {
+            "Showing full payloads".to_string()
+        };
+    }
+
+    /// Show the help view
+    pub fn show_help(&mut self) {
+        self.view_mode = ViewMode::Help;
+    }
+
+    /// Enter search mode
+    pub fn enter_search_mode(&mut self) {
+        self.input_mode = InputMode::Search;
+        self.command_input = String::new();
+        self.status_message = "Search: ".to_string();
+        self.search_origin = self.selected_message_idx;
+        self.history_pos = None;
+    }
+
+    /// Exit search mode
+    pub fn exit_search_mode(&mut self) {
         self.input_mode = InputMode::Normal;
+        self.incremental_scan = None;
         self.command_input = String::new();
         self.status_message = String::new();
     }
@@ -318,26 +1951,147 @@ impl App {
     pub fn handle_search_input(&mut self, key: char) {
         match key {
             '\n' | '\r' => {
-                // Execute search on Enter
+                // Execute search on Enter, keeping the match found while typing
                 let pattern = self.command_input.clone();
+                let selected = self
+                    .filtered_messages
+                    .get(self.selected_message_idx)
+                    .copied();
+                History::push(&mut self.history.search, &pattern);
+                self.exit_search_mode();
                 if !pattern.is_empty() {
-                    if let Err(e) = self.search(&pattern) {
-                        self.status_message = format!("Invalid search pattern: {}", e);
+                    if let Err(e) = self.start_search(&pattern, selected) {
+                        self.status_message = e.to_string();
                     }
                 }
-                self.exit_search_mode();
             }
             '\u{8}' | '\u{7f}' => {
                 // Backspace
                 self.command_input.pop();
+                self.incremental_search();
             }
             '\u{1b}' => {
-                // Escape
+                // Escape restores the selection from before the search
+                self.selected_message_idx = self.search_origin;
                 self.exit_search_mode();
             }
             _ => {
                 // Add character to input
                 self.command_input.push(key);
+                self.incremental_search();
+            }
+        }
+    }
+
+    /// Replace the input with the previous (older) history entry
+    pub fn history_prev(&mut self) {
+        let len = self.input_history().len();
+        let pos = match self.history_pos {
+            None if len > 0 => {
+                self.history_draft = self.command_input.clone();
+                len - 1
+            }
+            Some(pos) if pos > 0 => pos - 1,
+            _ => return,
+        };
+
+        self.history_pos = Some(pos);
+        self.command_input = self.input_history()[pos].clone();
+        self.history_input_changed();
+    }
+
+    /// Replace the input with the next (newer) history entry, or the draft at the end
+    pub fn history_next(&mut self) {
+        let Some(pos) = self.history_pos else {
+            return;
+        };
+
+        if pos + 1 < self.input_history().len() {
+            self.history_pos = Some(pos + 1);
+            self.command_input = self.input_history()[pos + 1].clone();
+        } else {
+            self.history_pos = None;
+            self.command_input = std::mem::take(&mut self.history_draft);
+        }
+        self.history_input_changed();
+    }
+
+    /// Get the history list for the current input mode
+    fn input_history(&self) -> &[String] {
+        match self.input_mode {
+            InputMode::Search => &self.history.search,
+            InputMode::Filter => &self.history.filter,
+            InputMode::Command => &self.history.command,
+            InputMode::Normal => &[],
+        }
+    }
+
+    /// Update the view after the input was replaced from the history
+    fn history_input_changed(&mut self) {
+        if self.input_mode == InputMode::Search {
+            self.incremental_search();
+        }
+    }
+
+    /// Jump to the first match of the pattern typed so far
+    ///
+    /// The scan starts at the selection from before the search and wraps
+    /// around. Incomplete (invalid) patterns leave the selection alone.
+    /// Only a chunk of the messages is scanned per key, so typing stays responsive
+    /// on large files; `continue_incremental_search` scans the rest.
+    fn incremental_search(&mut self) {
+        self.incremental_scan = None;
+        if self.command_input.is_empty() {
+            self.selected_message_idx = self.search_origin;
+            return;
+        }
+
+        let Ok(engine) =
+            SearchEngine::with_case_sensitivity(&self.command_input, self.case_sensitive_search)
+        else {
+            return;
+        };
+
+        self.incremental_scan = Some((engine, 0));
+        self.continue_incremental_search();
+    }
+
+    /// Scan the next chunk of messages for the pattern typed so far, selecting
+    /// the first match
+    ///
+    /// Returns whether the selection changed.
+    pub fn continue_incremental_search(&mut self) -> bool {
+        let Some((engine, scanned)) = &self.incremental_scan else {
+            return false;
+        };
+
+        // Scan in list order, without evicting the cached (visible) messages
+        let len = self.filtered_messages.len();
+        let end = (scanned + INCREMENTAL_SEARCH_CHUNK_SIZE).min(len);
+        let found = (*scanned..end)
+            .into_par_iter()
+            .map(|offset| (self.search_origin + offset) % len)
+            .find_first(|&pos| {
+                self.message_location(pos)
+                    .and_then(|(file_idx, msg_idx)| {
+                        self.files[file_idx].parse_message(msg_idx).ok()
+                    })
+                    .is_some_and(|msg| engine.matches(&msg))
+            });
+
+        match found {
+            Some(pos) => {
+                self.incremental_scan = None;
+                self.selected_message_idx = pos;
+                true
+            }
+            None => {
+                self.incremental_scan = self
+                    .incremental_scan
+                    .take()
+                    .filter(|_| end < len)
+                    .map(|(engine, _)| (engine, end));
+                false
             }
         }
     }
@@ -347,6 +2101,7 @@ impl App {
         self.input_mode = InputMode::Filter;
         self.command_input = String::new();
         self.status_message = "Filter: ".to_string();
+        self.history_pos = None;
     }
 
     /// Exit filter mode
@@ -362,12 +2117,13 @@ impl App {
             '\n' | '\r' => {
                 // Execute filter on Enter
                 let pattern = self.command_input.clone();
+                History::push(&mut self.history.filter, &pattern);
+                self.exit_filter_mode();
                 if !pattern.is_empty() {
-                    if let Err(e) = self.apply_text_filter(&pattern) {
-                        self.status_message = format!("Invalid filter pattern: {}", e);
+                    if let Err(e) = self.apply_filter_command(&pattern) {
+                        self.status_message = format!("Invalid filter: {}", e);
                     }
                 }
-                self.exit_filter_mode();
             }
             '\u{8}' | '\u{7f}' => {
                 // Backspace
@@ -384,40 +2140,345 @@ impl App {
         }
     }
 
-    /// Apply a text filter
-    pub fn apply_text_filter(&mut self, pattern: &str) -> Result<(), regex::Error> {
-        // Create a regex from the pattern
-        let regex = Regex::new(pattern)?;
-
+    /// Apply a filter command (e.g. `app=APP`, `time=14:00..14:05` or a text pattern)
+    pub fn apply_filter_command(&mut self, command: &str) -> FilterResult<()> {
         // Update the filter criteria
-        self.filter.text_pattern = Some(regex);
-
-        // Update the filter engine
-        if let Some(engine) = &mut self.filter_engine {
-            engine.set_criteria(self.filter.clone());
-        } else {
-            self.filter_engine = Some(FilterEngine::new(self.filter.clone()));
-        }
+        let date = self.reference_date();
+        self.filter.apply_command(command, date, self.timezone)?;
 
         // Apply the filter
-        self.apply_filter();
+        self.update_filter();
 
         // Update status message
         if self.filtered_messages.is_empty() {
-            self.status_message = format!("No messages match filter '{}'", pattern);
+            self.status_message = format!("No messages match filter '{}'", command);
         } else {
             self.status_message = format!(
                 "Showing {} messages matching filter '{}'",
                 self.filtered_messages.len(),
-                pattern
+                command
             );
         }
 
         Ok(())
     }
 
+    /// Replace the filter criteria and re-apply the filter
+    pub fn set_filter(&mut self, criteria: FilterCriteria) {
+        self.filter = criteria;
+        self.update_filter();
+    }
+
+    /// Toggle showing only error and fatal messages, restoring the previous filter afterwards
+    pub fn toggle_errors_only(&mut self) {
+        match self.errors_only_saved_filter.take() {
+            Some(saved) => {
+                self.set_filter(saved);
+                self.status_message = "Showing all log levels".to_string();
+            }
+            None => {
+                self.errors_only_saved_filter = Some(self.filter.clone());
+                let mut criteria = self.filter.clone();
+                criteria.log_level = None;
+                criteria.min_log_level = Some(LogLevel::Error);
+                self.set_filter(criteria);
+                self.status_message =
+                    format!("Errors only: {} messages", self.filtered_messages.len());
+            }
+        }
+    }
+
+    /// Filter to the application ID of the selected message
+    pub fn filter_to_selected_app(&mut self) {
+        let Some(app_id) = self.selected_message().and_then(|msg| msg.app_id()) else {
+            self.status_message = "Selected message has no application ID".to_string();
+            return;
+        };
+
+        self.filter.app_id = Some(app_id.clone());
+        self.update_filter_keeping_selection();
+        self.status_message = format!("Filtered to application '{}'", app_id);
+    }
+
+    /// Filter to the context ID of the selected message
+    pub fn filter_to_selected_context(&mut self) {
+        let Some(context_id) = self.selected_message().and_then(|msg| msg.context_id()) else {
+            self.status_message = "Selected message has no context ID".to_string();
+            return;
+        };
+
+        self.filter.context_id = Some(context_id.clone());
+        self.update_filter_keeping_selection();
+        self.status_message = format!("Filtered to context '{}'", context_id);
+    }
+
+    /// Hide all messages with the application ID of the selected message
+    pub fn exclude_selected_app(&mut self) {
+        let Some(app_id) = self.selected_message().and_then(|msg| msg.app_id()) else {
+            self.status_message = "Selected message has no application ID".to_string();
+            return;
+        };
+
+        self.filter.exclude_app_id(app_id.clone());
+        self.update_filter_keeping_position();
+        self.status_message = format!("Excluded application '{}'", app_id);
+    }
+
+    /// Hide all messages with the context ID of the selected message
+    pub fn exclude_selected_context(&mut self) {
+        let Some(context_id) = self.selected_message().and_then(|msg| msg.context_id()) else {
+            self.status_message = "Selected message has no context ID".to_string();
+            return;
+        };
+
+        self.filter.exclude_context_id(context_id.clone());
+        self.update_filter_keeping_position();
+        self.status_message = format!("Excluded context '{}'", context_id);
+    }
+
+    /// Show the excluded application and context IDs again
+    pub fn clear_exclusions(&mut self) {
+        if self.filter.excluded_app_ids.is_empty() && self.filter.excluded_context_ids.is_empty() {
+            self.status_message = "No excluded IDs".to_string();
+            return;
+        }
+
+        self.filter.clear_exclusions();
+        self.update_filter_keeping_selection();
+        self.status_message = "Cleared excluded IDs".to_string();
+    }
+
+    /// Select the next message with the application ID of the selected message
+    pub fn next_same_app(&mut self) {
+        self.select_same_id(true, "application", DltMessage::app_id);
+    }
+
+    /// Select the previous message with the application ID of the selected message
+    pub fn prev_same_app(&mut self) {
+        self.select_same_id(false, "application", DltMessage::app_id);
+    }
+
+    /// Select the next message with the context ID of the selected message
+    pub fn next_same_context(&mut self) {
+        self.select_same_id(true, "context", DltMessage::context_id);
+    }
+
+    /// Select the previous message with the context ID of the selected message
+    pub fn prev_same_context(&mut self) {
+        self.select_same_id(false, "context", DltMessage::context_id);
+    }
+
+    /// Select the nearest shown message in a direction with the same ID as the selected one
+    fn select_same_id(
+        &mut self,
+        forward: bool,
+        kind: &str,
+        id_of: fn(&DltMessage) -> Option<String>,
+    ) {
+        let Some(id) = self.selected_message().as_ref().and_then(id_of) else {
+            self.status_message = format!("Selected message has no {} ID", kind);
+            return;
+        };
+
+        let is_same = |pos: usize| {
+            self.message_at(pos)
+                .as_ref()
+                .and_then(id_of)
+                .is_some_and(|other| other == id)
+        };
+        let current = self.selected_message_idx;
+        let found = if forward {
+            (current + 1..self.filtered_messages.len()).find(|&pos| is_same(pos))
+        } else {
+            (0..current).rev().find(|&pos| is_same(pos))
+        };
+
+        match found {
+            Some(pos) => {
+                self.pause_following();
+                self.selected_message_idx = pos;
+            }
+            None => {
+                self.status_message = format!(
+                    "No {} message of {} '{}'",
+                    if forward { "later" } else { "earlier" },
+                    kind,
+                    id
+                );
+            }
+        }
+    }
+
+    /// Re-apply the filter and select the first message at or after the previous selection
+    fn update_filter_keeping_position(&mut self) {
+        let selected = self
+            .filtered_messages
+            .get(self.selected_message_idx)
+            .copied();
+
+        self.update_filter();
+
+        if let Some(idx) = selected {
+            let pos = self.filtered_messages.partition_point(|&i| i < idx);
+            self.selected_message_idx = pos.min(self.filtered_messages.len().saturating_sub(1));
+        }
+    }
+
+    /// Re-apply the filter and select the previously selected message if it is still shown
+    fn update_filter_keeping_selection(&mut self) {
+        let selected = self
+            .filtered_messages
+            .get(self.selected_message_idx)
+            .copied();
+
+        self.update_filter();
+
+        if let Some(pos) = selected.and_then(|idx| self.filtered_messages.binary_search(&idx).ok())
+        {
+            self.selected_message_idx = pos;
+        }
+    }
+
+    /// Update the filter engine with the current criteria and re-apply it
+    fn update_filter(&mut self) {
+        if let Some(engine) = &mut self.filter_engine {
+            engine.set_criteria(self.filter.clone());
+        } else {
+            self.filter_engine = Some(FilterEngine::new(self.filter.clone()));
+        }
+
+        self.apply_filter();
+    }
+
+    /// Get the date of the first message in view (in the display timezone), used to complete times of day
+    fn reference_date(&self) -> NaiveDate {
+        let first = if self.merged {
+            self.merged_messages.first().copied()
+        } else if self.files.is_empty() {
+            None
+        } else {
+            Some((self.current_file_idx, 0))
+        };
+
+        first
+            .and_then(|(file_idx, msg_idx)| self.files[file_idx].get_message(msg_idx).ok())
+            .map(|msg| self.timezone.date(msg.timestamp()))
+            .unwrap_or_else(|| self.timezone.date(Utc::now()))
+    }
+
+    /// Toggle the hex dump pane in the detail view
+    pub fn toggle_hex(&mut self) {
+        self.show_hex = !self.show_hex;
+    }
+
+    /// Start a marked range at the selected message, or clear the marked range
+    pub fn toggle_range(&mut self) {
+        if self.range_anchor.take().is_some() {
+            self.status_message = "Cleared the marked range".to_string();
+        } else if !self.filtered_messages.is_empty() {
+            self.range_anchor = Some(self.selected_message_idx);
+            self.status_message =
+                "Marking a range, move to extend it (:export PATH saves it)".to_string();
+        }
+    }
+
+    /// Get the marked range of positions in the filtered messages, if any
+    pub fn marked_range(&self) -> Option<RangeInclusive<usize>> {
+        let anchor = self.range_anchor?;
+        Some(anchor.min(self.selected_message_idx)..=anchor.max(self.selected_message_idx))
+    }
+
+    /// Mark the selected message to measure the time from, or clear the mark
+    pub fn toggle_measure(&mut self) {
+        if self.measure_anchor.take().is_some() {
+            self.status_message = "Cleared the measure mark".to_string();
+        } else if let Some(location) = self.message_location(self.selected_message_idx) {
+            self.measure_anchor = Some(location);
+            self.status_message =
+                "Measuring the time from the marked message, move to another one".to_string();
+        }
+    }
+
+    /// Get the time from the measure mark to the selected message, if marked
+    pub fn measured_time(&self) -> Option<TimeDelta> {
+        let (file_idx, msg_idx) = self.measure_anchor?;
+        let start = self.files.get(file_idx)?.get_message(msg_idx).ok()?;
+        let selected = self.selected_message()?;
+        Some(selected.timestamp() - start.timestamp())
+    }
+
+    /// Toggle the raw header bytes in the detail view
+    pub fn toggle_raw_header(&mut self) {
+        self.show_raw_header = !self.show_raw_header;
+    }
+
+    /// Toggle pretty-printing of JSON payloads in the detail view
+    pub fn toggle_pretty_json(&mut self) {
+        self.pretty_json = !self.pretty_json;
+        self.status_message = if self.pretty_json {
+            "Pretty-printing JSON payloads".to_string()
+        } else {
+            "Showing JSON payloads as logged".to_string()
+        };
+    }
+
+    /// Toggle between showing the oldest and the newest message first
+    pub fn toggle_reversed(&mut self) {
+        self.reversed = !self.reversed;
+        self.status_message = if self.reversed {
+            "Showing newest messages first".to_string()
+        } else {
+            "Showing oldest messages first".to_string()
+        };
+    }
+
+    /// Toggle the column of message indices in the list
+    pub fn toggle_line_numbers(&mut self) {
+        self.show_line_numbers = !self.show_line_numbers;
+        self.status_message = if self.show_line_numbers {
+            "Showing message indices".to_string()
+        } else {
+            "Hiding message indices".to_string()
+        };
+    }
+
+    /// Toggle between absolute and relative timestamps
+    pub fn toggle_relative_timestamps(&mut self) {
+        self.relative_timestamps = !self.relative_timestamps;
+        self.status_message = if self.relative_timestamps {
+            "Showing relative timestamps".to_string()
+        } else {
+            "Showing absolute timestamps".to_string()
+        };
+    }
+
+    /// Use the selected message as the anchor for relative timestamps
+    pub fn set_anchor(&mut self) {
+        let Some(location) = self.message_location(self.selected_message_idx) else {
+            self.status_message = "No message selected".to_string();
+            return;
+        };
+
+        self.time_anchor = Some(location);
+        self.relative_timestamps = true;
+        self.status_message = "Anchor set to the selected message".to_string();
+    }
+
+    /// Anchor relative timestamps to the first filtered message again
+    pub fn clear_anchor(&mut self) {
+        self.time_anchor = None;
+        self.status_message = "Anchor cleared".to_string();
+    }
+
+    /// Get the timestamp that relative timestamps are measured from
+    pub fn anchor_time(&self) -> Option<DateTime<Utc>> {
+        let (file_idx, msg_idx) = self.time_anchor.or_else(|| self.message_location(0))?;
+        let msg = self.files.get(file_idx)?.get_message(msg_idx).ok()?;
+        Some(msg.timestamp())
+    }
+
     /// Toggle case sensitivity for search
-    pub fn toggle_case_sensitivity(&mut self) -> Result<(), regex::Error> {
+    pub fn toggle_case_sensitivity(&mut self) -> AppResult<()> {
         // Toggle the flag
         self.case_sensitive_search = !self.case_sensitive_search;
 
@@ -435,15 +2496,265 @@ impl App {
         self.status_message = format!("Search mode: {}", mode);
 
         // Re-run the search if there's an active search pattern
-        if let Some(pattern) = self.search_pattern.as_ref().map(|r| r.as_str().to_string()) {
-            self.search(&pattern)?;
+        if let Some(query) = self.search_query.clone() {
+            self.search(&query)?;
         }
 
         Ok(())
     }
 
+    /// Highlight the matches of a pattern in the list in a color, replacing the
+    /// color if the pattern is already highlighted
+    ///
+    /// Like the search pattern, the pattern ignores case unless searches are case
+    /// sensitive.
+    pub fn add_highlight(&mut self, pattern: &str, color: Color) -> AppResult<()> {
+        let regex = if self.case_sensitive_search {
+            Regex::new(pattern)?
+        } else {
+            Regex::new(&format!("(?i){}", pattern))?
+        };
+
+        match self
+            .highlights
+            .iter_mut()
+            .find(|(existing, _)| existing.as_str() == regex.as_str())
+        {
+            Some(highlight) => highlight.1 = color,
+            None => self.highlights.push((regex, color)),
+        }
+        Ok(())
+    }
+
+    /// Stop highlighting a pattern, returning whether it was highlighted
+    pub fn remove_highlight(&mut self, pattern: &str) -> bool {
+        let count = self.highlights.len();
+        let ignoring_case = format!("(?i){}", pattern);
+        self.highlights
+            .retain(|(regex, _)| regex.as_str() != pattern && regex.as_str() != ignoring_case);
+        self.highlights.len() < count
+    }
+
     /// Exit the application
     pub fn exit(&mut self) {
         self.should_exit = true;
     }
 }
+
+/// Search a chunk of message locations, returning the message indices of the
+/// matches in ascending order
+///
+/// `indices` holds the entries of the filtered messages for the locations.
+fn search_chunk(
+    engine: &SearchEngine,
+    files: &[Arc<DltFile>],
+    locations: &[Option<(usize, usize)>],
+    indices: &[usize],
+) -> Vec<usize> {
+    locations
+        .par_iter()
+        .zip(indices)
+        .filter_map(|(location, &idx)| {
+            let (file_idx, msg_idx) = (*location)?;
+            let msg = files[file_idx].parse_message(msg_idx).ok()?;
+            engine.matches(&msg).then_some(idx)
+        })
+        .collect()
+}
+
+/// Write the messages at the given locations to a file in chunks
+///
+/// `report` is called with the number of messages written after each chunk, and
+/// stops the export early by returning false.
+fn export_chunks(
+    file: File,
+    files: &[Arc<DltFile>],
+    locations: &[Option<(usize, usize)>],
+    format: OutputFormat,
+    timezone: Timezone,
+    timestamp_format: &str,
+    mut report: impl FnMut(usize) -> bool,
+) -> io::Result<usize> {
+    let mut out = BufWriter::new(file);
+    let mut written = 0;
+    for chunk in locations.chunks(EXPORT_CHUNK_SIZE) {
+        // Parse the chunk in parallel, then write it in order
+        let messages: Vec<DltMessage> = chunk
+            .par_iter()
+            .filter_map(|location| {
+                let (file_idx, msg_idx) = (*location)?;
+                files[file_idx].parse_message(msg_idx).ok()
+            })
+            .collect();
+        written += write_messages(&mut out, messages, format, timezone, timestamp_format)?;
+
+        if !report(written) {
+            return Ok(written);
+        }
+    }
+    out.flush()?;
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::Action;
+    use crate::testing;
+    use tempfile::NamedTempFile;
+
+    /// Open an app on a file of `count` messages, alternating between APP1 (even
+    /// indices) and APP2, where every third message says "match"
+    fn open_app(count: usize) -> (App, NamedTempFile) {
+        let messages: Vec<Vec<u8>> = (0..count)
+            .map(|i| {
+                let app_id = if i % 2 == 0 { "APP1" } else { "APP2" };
+                let text = if i % 3 == 0 { "match" } else { "other" };
+                testing::message(i as u32, app_id, "CTX1", 4, &format!("{} {}", text, i))
+            })
+            .collect();
+        let tmp = testing::write_file(&messages);
+
+        let mut app = App::new(Settings::default());
+        app.load_file(tmp.path().to_path_buf()).unwrap();
+        app.set_viewport_height(10);
+        (app, tmp)
+    }
+
+    /// Get the index in the file of the selected message
+    fn selected(app: &App) -> usize {
+        app.filtered_messages[app.selected_message_idx]
+    }
+
+    #[test]
+    fn search_results_follow_filter_changes_in_file_order() {
+        let (mut app, _tmp) = open_app(30);
+        app.search("match").unwrap();
+        assert_eq!(app.search_results, (0..30).step_by(3).collect::<Vec<_>>());
+        assert_eq!(selected(&app), 0);
+
+        app.perform(Action::NextMatch);
+        app.perform(Action::NextMatch);
+        assert_eq!(selected(&app), 6);
+
+        // Only the even messages are shown; results the filter hides are skipped
+        app.apply_filter_command("app=APP1").unwrap();
+        assert_eq!(app.search_result_positions().len(), 5);
+        app.perform(Action::NextMatch);
+        assert_eq!(selected(&app), 12);
+        app.perform(Action::NextMatch);
+        assert_eq!(selected(&app), 18);
+        app.perform(Action::PrevMatch);
+        app.perform(Action::PrevMatch);
+        app.perform(Action::PrevMatch);
+        assert_eq!(selected(&app), 0);
+        app.perform(Action::PrevMatch);
+        assert_eq!(selected(&app), 24);
+
+        // Showing all messages again brings the hidden results back
+        app.set_filter(FilterCriteria::new());
+        app.perform(Action::NextMatch);
+        assert_eq!(selected(&app), 27);
+        app.perform(Action::NextMatch);
+        assert_eq!(selected(&app), 0);
+        app.perform(Action::NextMatch);
+        assert_eq!(selected(&app), 3);
+    }
+
+    /// Open an app whose filter hides all messages, after searching for "match"
+    fn open_empty_app() -> (App, NamedTempFile) {
+        let (mut app, tmp) = open_app(30);
+        app.search("match").unwrap();
+        app.apply_filter_command("app=NONE").unwrap();
+        assert!(app.filtered_messages.is_empty());
+        (app, tmp)
+    }
+
+    #[test]
+    fn empty_filter_result_has_no_selection() {
+        let (app, _tmp) = open_empty_app();
+        assert_eq!(app.selected_message_idx, 0);
+        assert!(app.selected_message().is_none());
+        assert_eq!(
+            app.no_messages_text(),
+            "No messages match the current filter"
+        );
+    }
+
+    #[test]
+    fn navigation_is_a_no_op_without_messages() {
+        let (mut app, _tmp) = open_empty_app();
+        for action in [
+            Action::NextMatch,
+            Action::PrevMatch,
+            Action::MoveToBottom,
+            Action::MoveToTop,
+            Action::MoveDown,
+            Action::MoveUp,
+        ] {
+            app.perform(action);
+            assert_eq!(app.selected_message_idx, 0, "{:?}", action);
+        }
+        assert_eq!(
+            app.status_message,
+            "None of the 10 matches is shown by the filter"
+        );
+    }
+
+    #[test]
+    fn search_without_messages_finds_nothing() {
+        let (mut app, _tmp) = open_empty_app();
+        app.perform(Action::SearchMode);
+        for key in "match\r".chars() {
+            app.handle_search_input(key);
+        }
+        assert_eq!(app.selected_message_idx, 0);
+        assert!(app.search_result_positions().is_empty());
+        app.perform(Action::NextMatch);
+        assert_eq!(app.selected_message_idx, 0);
+    }
+
+    #[test]
+    fn count_and_export_without_messages() {
+        let (mut app, _tmp) = open_empty_app();
+        app.execute_command("count match");
+        assert_eq!(app.status_message, "0 of 0 messages match 'match'");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.txt");
+        app.execute_command(&format!("export {}", path.display()));
+        assert_eq!(app.status_message, "Nothing to export");
+        assert!(!path.exists());
+        assert!(!app.is_exporting());
+    }
+
+    #[test]
+    fn toggling_case_sensitivity_re_runs_the_search() {
+        let messages = ["Error 1", "error 2", "ERROR 3", "warning 4"]
+            .iter()
+            .enumerate()
+            .map(|(i, text)| testing::message(i as u32, "APP1", "CTX1", 4, text))
+            .collect::<Vec<_>>();
+        let tmp = testing::write_file(&messages);
+        let mut app = App::new(Settings {
+            default_case_sensitive: false,
+            ..Default::default()
+        });
+        app.load_file(tmp.path().to_path_buf()).unwrap();
+
+        app.search("error").unwrap();
+        assert_eq!(app.search_results, [0, 1, 2]);
+
+        app.toggle_case_sensitivity().unwrap();
+        assert!(app.case_sensitive_search);
+        assert_eq!(app.search_results, [1]);
+
+        app.toggle_case_sensitivity().unwrap();
+        assert_eq!(app.search_results, [0, 1, 2]);
+        assert_eq!(
+            app.search_pattern.as_ref().map(Regex::as_str),
+            Some("(?i)error")
+        );
+    }
+}
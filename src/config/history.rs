@@ -0,0 +1,85 @@
+// History
+//
+// This file defines the search, filter and command input history, and the
+// recently opened files.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::config::Settings;
+
+/// Maximum number of entries kept per history list
+const MAX_HISTORY_LEN: usize = 100;
+
+/// Search, filter and command input history, and recently opened files
+///
+/// This is state kept between sessions, saved separately from the settings so
+/// the user's config file is never rewritten.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct History {
+    /// Committed search patterns, oldest first
+    pub search: Vec<String>,
+    /// Committed filter commands, oldest first
+    pub filter: Vec<String>,
+    /// Committed `:` commands, oldest first
+    pub command: Vec<String>,
+    /// Recently opened files, most recent first
+    pub recent_files: Vec<PathBuf>,
+}
+
+impl History {
+    /// Load the history from a file
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let history =
+            toml::from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(history)
+    }
+
+    /// Save the history to a file
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, content)
+    }
+
+    /// Add an entry to a history list, skipping consecutive duplicates
+    pub fn push(list: &mut Vec<String>, entry: &str) {
+        if entry.is_empty() || list.last().is_some_and(|last| last == entry) {
+            return;
+        }
+
+        list.push(entry.to_string());
+
+        // Drop the oldest entries if the list is too long
+        if list.len() > MAX_HISTORY_LEN {
+            list.drain(..list.len() - MAX_HISTORY_LEN);
+        }
+    }
+
+    /// Add a file to the recent files, keeping at most `max` of them
+    pub fn add_recent_file(&mut self, path: PathBuf, max: usize) {
+        // Move the file to the front if it is already listed
+        self.recent_files.retain(|p| p != &path);
+        self.recent_files.insert(0, path);
+        self.recent_files.truncate(max);
+    }
+
+    /// Get the default history path (next to the config file)
+    pub fn default_path() -> PathBuf {
+        Settings::default_path().with_file_name("history.toml")
+    }
+
+    /// Load the history from the default path
+    pub fn load_default() -> Self {
+        Self::load(Self::default_path()).unwrap_or_default()
+    }
+
+    /// Save the history to the default path
+    pub fn save_default(&self) -> io::Result<()> {
+        self.save(Self::default_path())
+    }
+}
@@ -2,6 +2,12 @@
 //
 // This module handles configuration settings.
 
+mod history;
+mod session;
 mod settings;
+mod timezone;
 
+pub use history::History;
+pub use session::{Session, SessionFile};
 pub use settings::Settings;
+pub use timezone::Timezone;
@@ -0,0 +1,67 @@
+// Session
+//
+// This file defines the session state saved on exit and restored with
+// `--resume`: the open files, the selection in each file and the filter.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::config::Settings;
+use crate::filter::FilterCriteriaConfig;
+
+/// An open file of a session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionFile {
+    /// Path of the file
+    pub path: PathBuf,
+    /// Index of the selected message in the file
+    pub message_idx: Option<usize>,
+}
+
+/// State of the last session
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// Path of the file shown when the session ended
+    pub current_file: Option<PathBuf>,
+    /// Whether the files were shown merged
+    pub merged: bool,
+    /// Active filter
+    pub filter: FilterCriteriaConfig,
+    /// Open files, in the order they were opened
+    pub files: Vec<SessionFile>,
+}
+
+impl Session {
+    /// Load the session from a file
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let session =
+            toml::from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(session)
+    }
+
+    /// Save the session to a file
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, content)
+    }
+
+    /// Get the default session path (next to the config file)
+    pub fn default_path() -> PathBuf {
+        Settings::default_path().with_file_name("session.toml")
+    }
+
+    /// Load the session from the default path
+    pub fn load_default() -> io::Result<Self> {
+        Self::load(Self::default_path())
+    }
+
+    /// Save the session to the default path
+    pub fn save_default(&self) -> io::Result<()> {
+        self.save(Self::default_path())
+    }
+}
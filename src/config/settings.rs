@@ -2,13 +2,26 @@
 //
 // This file defines the application settings.
 
+use crate::parser::DEFAULT_CACHE_SIZE;
+use chrono::format::{Item, StrftimeItems};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 
+/// Default timestamp format in the log list
+pub const DEFAULT_LIST_TIMESTAMP_FORMAT: &str = "%H:%M:%S%.3f";
+/// Default maximum number of payload bytes shown per message
+pub const DEFAULT_MAX_PAYLOAD_DISPLAY: usize = 64 * 1024;
+/// Default timestamp format in the detail view
+pub const DEFAULT_DETAIL_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.6f";
+
 /// Application settings
+///
+/// Missing fields in the config file fall back to their defaults.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
     /// Tick rate in milliseconds
     pub tick_rate: u64,
@@ -18,12 +31,38 @@ pub struct Settings {
     pub default_app_id: Option<String>,
     /// Default context ID filter
     pub default_context_id: Option<String>,
-    /// Recent files
-    pub recent_files: Vec<PathBuf>,
-    /// Maximum number of recent files
+    /// Compare application and context IDs in filters ignoring case
+    pub case_insensitive_ids: bool,
+    /// Whether searches start out case sensitive (`i` toggles it at runtime)
+    pub default_case_sensitive: bool,
+    /// Whether every search result is tagged in the list, not only the current one
+    pub show_match_indicator: bool,
+    /// Maximum number of recent files (kept in the history)
     pub max_recent_files: usize,
     /// Theme name
     pub theme: String,
+    /// Order of the files in the file browser: "load", "name", "modified" or "messages"
+    pub file_sort: String,
+    /// Capture mouse events for clicking and scrolling (disable to select text
+    /// with the mouse)
+    pub mouse: bool,
+    /// Number of parsed messages cached per file (0 disables the cache)
+    pub message_cache_size: usize,
+    /// Maximum number of payload bytes shown per message
+    pub max_payload_display: usize,
+    /// Remove ANSI escape sequences (e.g. colors) from displayed payloads
+    pub strip_ansi: bool,
+    /// Directory for relative `:export` and `:dump` paths, where the file picker
+    /// starts (the current directory if unset)
+    pub work_dir: Option<PathBuf>,
+    /// Timezone for timestamps: "utc", "local" or an offset like "+01:00"
+    pub timezone: String,
+    /// Timestamp format (chrono strftime syntax) in the log list
+    pub list_timestamp_format: String,
+    /// Timestamp format (chrono strftime syntax) in the detail view
+    pub detail_timestamp_format: String,
+    /// Key binding overrides, from key (e.g. "ctrl+d") to action name
+    pub keys: BTreeMap<String, String>,
 }
 
 impl Default for Settings {
@@ -33,9 +72,21 @@ impl Default for Settings {
             default_log_level: None,
             default_app_id: None,
             default_context_id: None,
-            recent_files: Vec::new(),
+            case_insensitive_ids: false,
+            default_case_sensitive: true,
+            show_match_indicator: true,
             max_recent_files: 10,
             theme: "default".to_string(),
+            file_sort: "load".to_string(),
+            mouse: true,
+            message_cache_size: DEFAULT_CACHE_SIZE,
+            max_payload_display: DEFAULT_MAX_PAYLOAD_DISPLAY,
+            strip_ansi: true,
+            work_dir: None,
+            timezone: "utc".to_string(),
+            list_timestamp_format: DEFAULT_LIST_TIMESTAMP_FORMAT.to_string(),
+            detail_timestamp_format: DEFAULT_DETAIL_TIMESTAMP_FORMAT.to_string(),
+            keys: BTreeMap::new(),
         }
     }
 }
@@ -56,18 +107,49 @@ impl Settings {
         fs::write(path, content)
     }
 
-    /// Add a file to the recent files list
-    pub fn add_recent_file(&mut self, path: PathBuf) {
-        // Remove the file if it already exists
-        self.recent_files.retain(|p| p != &path);
-
-        // Add the file to the beginning of the list
-        self.recent_files.insert(0, path);
+    /// Reset invalid timestamp formats to their defaults, returning a warning for each
+    pub fn validate_timestamp_formats(&mut self) -> Vec<String> {
+        let mut warnings = Vec::new();
 
-        // Truncate the list if it's too long
-        if self.recent_files.len() > self.max_recent_files {
-            self.recent_files.truncate(self.max_recent_files);
+        for (name, format, default) in [
+            (
+                "list_timestamp_format",
+                &mut self.list_timestamp_format,
+                DEFAULT_LIST_TIMESTAMP_FORMAT,
+            ),
+            (
+                "detail_timestamp_format",
+                &mut self.detail_timestamp_format,
+                DEFAULT_DETAIL_TIMESTAMP_FORMAT,
+            ),
+        ] {
+            if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+                warnings.push(format!("Ignoring invalid {} '{}'", name, format));
+                *format = default.to_string();
+            }
         }
+
+        warnings
+    }
+
+    /// Get the working directory: `work_dir` (with `~` expanded), created if
+    /// missing, or the current directory
+    pub fn work_dir(&self) -> PathBuf {
+        let Some(dir) = &self.work_dir else {
+            return std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        };
+
+        let dir = match (dir.strip_prefix("~"), dirs::home_dir()) {
+            (Ok(rest), Some(home)) => home.join(rest),
+            _ => dir.clone(),
+        };
+        fs::create_dir_all(&dir).ok();
+        dir
+    }
+
+    /// Resolve a path typed by the user against the working directory
+    pub fn work_path(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.work_dir().join(path)
     }
 
     /// Get the default config path
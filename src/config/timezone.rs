@@ -0,0 +1,81 @@
+// Timezone
+//
+// This file defines the timezone used to display and enter timestamps.
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+/// Timezone for displaying timestamps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Timezone {
+    /// Coordinated Universal Time
+    #[default]
+    Utc,
+    /// The local timezone of the system
+    Local,
+    /// A fixed offset from UTC
+    Fixed(FixedOffset),
+}
+
+impl Timezone {
+    /// Parse a timezone name: `utc`, `local` or an offset like `+01:00`, `-0530` or `+9`
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim();
+        match name.to_ascii_lowercase().as_str() {
+            "utc" | "z" | "" => return Some(Timezone::Utc),
+            "local" => return Some(Timezone::Local),
+            _ => {}
+        }
+
+        // Parse the sign, hours and optional minutes of the offset
+        let sign = match name.as_bytes()[0] {
+            b'+' => 1,
+            b'-' => -1,
+            _ => return None,
+        };
+        let digits = name[1..].replace(':', "");
+        if digits.is_empty() || digits.len() > 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let (hours, minutes) = if digits.len() > 2 {
+            digits.split_at(digits.len() - 2)
+        } else {
+            (digits.as_str(), "0")
+        };
+        let seconds = hours.parse::<i32>().ok()? * 3600 + minutes.parse::<i32>().ok()? * 60;
+
+        FixedOffset::east_opt(sign * seconds).map(Timezone::Fixed)
+    }
+
+    /// Format a timestamp in this timezone
+    pub fn format(&self, time: DateTime<Utc>, format: &str) -> String {
+        match self {
+            Timezone::Utc => time.format(format).to_string(),
+            Timezone::Local => time.with_timezone(&Local).format(format).to_string(),
+            Timezone::Fixed(offset) => time.with_timezone(offset).format(format).to_string(),
+        }
+    }
+
+    /// Get the date of a timestamp in this timezone
+    pub fn date(&self, time: DateTime<Utc>) -> NaiveDate {
+        match self {
+            Timezone::Utc => time.date_naive(),
+            Timezone::Local => time.with_timezone(&Local).date_naive(),
+            Timezone::Fixed(offset) => time.with_timezone(offset).date_naive(),
+        }
+    }
+
+    /// Convert a wall-clock time in this timezone to UTC
+    pub fn utc_time(&self, time: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            Timezone::Utc => Some(time.and_utc()),
+            Timezone::Local => Local
+                .from_local_datetime(&time)
+                .earliest()
+                .map(|t| t.with_timezone(&Utc)),
+            Timezone::Fixed(offset) => offset
+                .from_local_datetime(&time)
+                .single()
+                .map(|t| t.with_timezone(&Utc)),
+        }
+    }
+}
@@ -0,0 +1,87 @@
+// Message Formatting
+//
+// This file formats DLT messages as single lines of text, mirroring the log
+// list, or as JSON objects (one per line).
+
+use serde::Serialize;
+use std::borrow::Cow;
+use std::path::Path;
+
+use crate::config::Timezone;
+use crate::parser::DltMessage;
+
+/// Timestamp format used in JSON output
+const JSON_TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.6f%:z";
+
+/// Output format for messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// One line per message, like the log list
+    #[default]
+    Text,
+    /// One JSON object per message and line
+    Json,
+}
+
+impl OutputFormat {
+    /// Pick the format for a file from its extension: JSON for `.json` and `.jsonl`, text otherwise
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") || ext.eq_ignore_ascii_case("jsonl") => {
+                OutputFormat::Json
+            }
+            _ => OutputFormat::Text,
+        }
+    }
+}
+
+/// Fields of a message in JSON output
+#[derive(Serialize)]
+struct MessageRecord {
+    timestamp: String,
+    ecu_id: String,
+    app_id: Option<String>,
+    context_id: Option<String>,
+    log_level: Option<Cow<'static, str>>,
+    message_type: &'static str,
+    counter: u8,
+    payload: String,
+}
+
+/// Format a message as a single line
+///
+/// `timestamp_format` is used for text output; JSON output always uses
+/// RFC 3339 timestamps.
+pub fn format_message(
+    msg: &DltMessage,
+    format: OutputFormat,
+    timezone: Timezone,
+    timestamp_format: &str,
+) -> String {
+    match format {
+        OutputFormat::Text => format!(
+            "{} {:4} {:4} [{}] {}",
+            timezone.format(msg.timestamp(), timestamp_format),
+            msg.app_id().unwrap_or_default(),
+            msg.context_id().unwrap_or_default(),
+            msg.log_level().map_or_else(
+                || format!("{:?}", msg.message_type()),
+                |level| format!("{:?}", level)
+            ),
+            msg.payload_line(usize::MAX, false, None)
+        ),
+        OutputFormat::Json => {
+            let record = MessageRecord {
+                timestamp: timezone.format(msg.timestamp(), JSON_TIMESTAMP_FORMAT),
+                ecu_id: msg.ecu_id(),
+                app_id: msg.app_id(),
+                context_id: msg.context_id(),
+                log_level: msg.log_level().map(|level| level.name()),
+                message_type: msg.message_type().name(),
+                counter: msg.standard_header.message_counter,
+                payload: msg.payload_as_text(),
+            };
+            serde_json::to_string(&record).unwrap_or_default()
+        }
+    }
+}
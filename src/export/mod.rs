@@ -0,0 +1,10 @@
+// Export Module
+//
+// This module formats DLT messages as text or JSON and writes them to files or
+// stdout.
+
+mod format;
+mod writer;
+
+pub use format::{format_message, OutputFormat};
+pub use writer::write_messages;
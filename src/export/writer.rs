@@ -0,0 +1,30 @@
+// Message Writer
+//
+// This file writes formatted messages to a file or stdout, one per line.
+
+use std::io::{self, Write};
+
+use crate::config::Timezone;
+use crate::export::{format_message, OutputFormat};
+use crate::parser::DltMessage;
+
+/// Write messages one per line, returning the number of messages written
+pub fn write_messages(
+    out: &mut impl Write,
+    messages: impl IntoIterator<Item = DltMessage>,
+    format: OutputFormat,
+    timezone: Timezone,
+    timestamp_format: &str,
+) -> io::Result<usize> {
+    let mut count = 0;
+    for msg in messages {
+        writeln!(
+            out,
+            "{}",
+            format_message(&msg, format, timezone, timestamp_format)
+        )?;
+        count += 1;
+    }
+
+    Ok(count)
+}
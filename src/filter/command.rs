@@ -0,0 +1,159 @@
+// Filter Commands
+//
+// This file parses filter commands (as typed after `:filter`) and applies
+// them to the filter criteria.
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use regex::Regex;
+
+use crate::config::Timezone;
+use crate::filter::{Error, FilterCriteria, Result};
+use crate::parser::{LogLevel, MessageType};
+
+impl FilterCriteria {
+    /// Apply a filter command
+    ///
+    /// A command is `clear`, one or more clauses separated by spaces (e.g.
+    /// `app=NET ctx=TCP level>=warning`), or a regex on the payload text.
+    /// Supported clauses are `app=APP`, `ctx=CTX`, `level=LEVEL` (a name or a
+    /// raw value such as `7`), `level>=LEVEL`, `type=TYPE` (`log`, `trace`,
+    /// `network` or `control`) and `time=START..END`; an empty value (e.g.
+    /// `app=`) clears that field. Times are given as `HH:MM[:SS[.fff]]` on `date`
+    /// in `timezone`, and either end of the range may be left out (e.g.
+    /// `time=..14:05:00`).
+    ///
+    /// The clauses are applied together: if one of them is invalid, the
+    /// criteria are left unchanged.
+    pub fn apply_command(
+        &mut self,
+        command: &str,
+        date: NaiveDate,
+        timezone: Timezone,
+    ) -> Result<()> {
+        let command = command.trim();
+
+        if command == "clear" {
+            self.clear();
+            return Ok(());
+        }
+
+        // Commands starting with a clause are parsed as clauses, anything else
+        // is a text pattern (which may contain spaces)
+        let starts_with_clause = command
+            .split_whitespace()
+            .next()
+            .is_some_and(|word| split_clause(word).is_some());
+        if !starts_with_clause {
+            self.text_pattern = Some(Regex::new(command)?);
+            return Ok(());
+        }
+
+        let mut criteria = self.clone();
+        for word in command.split_whitespace() {
+            let (key, value) = split_clause(word).ok_or_else(|| Error::Clause(word.to_string()))?;
+            criteria.apply_clause(key, value, date, timezone)?;
+        }
+        *self = criteria;
+        Ok(())
+    }
+
+    /// Apply a single `KEY=VALUE` (or `level>=VALUE`) clause, where an empty
+    /// value clears the field
+    fn apply_clause(
+        &mut self,
+        key: &str,
+        value: &str,
+        date: NaiveDate,
+        timezone: Timezone,
+    ) -> Result<()> {
+        let value = Some(value).filter(|value| !value.is_empty());
+        match key {
+            "app" | "app_id" => self.app_id = value.map(str::to_string),
+            "ctx" | "ctx_id" | "context" => self.context_id = value.map(str::to_string),
+            "level" => {
+                self.log_level = value
+                    .map(|value| {
+                        LogLevel::from_name(value).ok_or_else(|| Error::LogLevel(value.to_string()))
+                    })
+                    .transpose()?;
+            }
+            "level>" => {
+                self.min_log_level = value
+                    .map(|value| {
+                        LogLevel::from_name(value).ok_or_else(|| Error::LogLevel(value.to_string()))
+                    })
+                    .transpose()?;
+            }
+            "type" => {
+                self.message_type = value
+                    .map(|value| {
+                        MessageType::from_name(value)
+                            .ok_or_else(|| Error::MessageType(value.to_string()))
+                    })
+                    .transpose()?;
+            }
+            "time" => {
+                self.time_range = value
+                    .map(|value| parse_time_range(value, date, timezone))
+                    .transpose()?;
+            }
+            _ => {
+                return Err(Error::Clause(format!(
+                    "{}={}",
+                    key,
+                    value.unwrap_or_default()
+                )))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Split a clause into its key and value, if it is one
+///
+/// `level>=VALUE` is returned with the key `level>`.
+fn split_clause(word: &str) -> Option<(&str, &str)> {
+    let (key, value) = word.split_once('=')?;
+    matches!(
+        key,
+        "app" | "app_id" | "ctx" | "ctx_id" | "context" | "level" | "level>" | "type" | "time"
+    )
+    .then_some((key, value))
+}
+
+/// Parse a time of day (`HH:MM[:SS[.fff]]`) on the given date in `timezone`
+pub fn parse_time_of_day(
+    value: &str,
+    date: NaiveDate,
+    timezone: Timezone,
+) -> Option<DateTime<Utc>> {
+    ["%H:%M:%S%.f", "%H:%M"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(value, format).ok())
+        .and_then(|time| timezone.utc_time(date.and_time(time)))
+}
+
+/// Parse a time range (`START..END`), where either end may be empty
+fn parse_time_range(
+    value: &str,
+    date: NaiveDate,
+    timezone: Timezone,
+) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+    let (start, end) = value
+        .split_once("..")
+        .ok_or_else(|| Error::Time(format!("expected START..END, got '{}'", value)))?;
+
+    let parse_bound = |bound: &str, open: DateTime<Utc>| {
+        let bound = bound.trim();
+        if bound.is_empty() {
+            Ok(open)
+        } else {
+            parse_time_of_day(bound, date, timezone).ok_or_else(|| Error::Time(bound.to_string()))
+        }
+    };
+
+    Ok((
+        parse_bound(start, DateTime::<Utc>::MIN_UTC)?,
+        parse_bound(end, DateTime::<Utc>::MAX_UTC)?,
+    ))
+}
@@ -0,0 +1,242 @@
+// Filter Configuration
+//
+// This file defines the serializable form of the filter criteria, used to
+// store filters in the configuration.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{Error, FilterCriteria, Result};
+use crate::parser::{LogLevel, MessageType};
+
+/// Filter criteria as stored in the configuration
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilterCriteriaConfig {
+    /// Application ID
+    pub app_id: Option<String>,
+    /// Context ID
+    pub context_id: Option<String>,
+    /// Log level name (e.g. "warn")
+    pub log_level: Option<String>,
+    /// Minimum log level name, showing this and more severe levels
+    pub min_log_level: Option<String>,
+    /// Start of the time range (RFC 3339)
+    pub time_start: Option<String>,
+    /// End of the time range (RFC 3339)
+    pub time_end: Option<String>,
+    /// Message type name (e.g. "log")
+    pub message_type: Option<String>,
+    /// Regex matched against the payload text
+    pub text_pattern: Option<String>,
+    /// Excluded application IDs
+    pub excluded_app_ids: Vec<String>,
+    /// Excluded context IDs
+    pub excluded_context_ids: Vec<String>,
+    /// Compare application and context IDs ignoring case
+    pub case_insensitive_ids: bool,
+}
+
+impl From<&FilterCriteria> for FilterCriteriaConfig {
+    fn from(criteria: &FilterCriteria) -> Self {
+        // Open ends of the range are left out
+        let (time_start, time_end) = match criteria.time_range {
+            Some((start, end)) => (
+                (start != DateTime::<Utc>::MIN_UTC).then(|| start.to_rfc3339()),
+                (end != DateTime::<Utc>::MAX_UTC).then(|| end.to_rfc3339()),
+            ),
+            None => (None, None),
+        };
+
+        Self {
+            app_id: criteria.app_id.clone(),
+            context_id: criteria.context_id.clone(),
+            log_level: criteria.log_level.map(|level| level.name().to_string()),
+            min_log_level: criteria.min_log_level.map(|level| level.name().to_string()),
+            time_start,
+            time_end,
+            message_type: criteria.message_type.map(|t| t.name().to_string()),
+            text_pattern: criteria
+                .text_pattern
+                .as_ref()
+                .map(|p| p.as_str().to_string()),
+            excluded_app_ids: criteria.excluded_app_ids.clone(),
+            excluded_context_ids: criteria.excluded_context_ids.clone(),
+            case_insensitive_ids: criteria.case_insensitive_ids,
+        }
+    }
+}
+
+impl TryFrom<FilterCriteriaConfig> for FilterCriteria {
+    type Error = Error;
+
+    fn try_from(config: FilterCriteriaConfig) -> Result<Self> {
+        let mut criteria = FilterCriteria::new();
+        criteria.app_id = config.app_id;
+        criteria.context_id = config.context_id;
+        criteria.excluded_app_ids = config.excluded_app_ids;
+        criteria.excluded_context_ids = config.excluded_context_ids;
+        criteria.case_insensitive_ids = config.case_insensitive_ids;
+
+        if let Some(level) = &config.log_level {
+            let level = LogLevel::from_name(level).ok_or_else(|| Error::LogLevel(level.clone()))?;
+            criteria.log_level = Some(level);
+        }
+
+        if let Some(level) = &config.min_log_level {
+            let level = LogLevel::from_name(level).ok_or_else(|| Error::LogLevel(level.clone()))?;
+            criteria.min_log_level = Some(level);
+        }
+
+        if let Some(message_type) = &config.message_type {
+            let message_type = MessageType::from_name(message_type)
+                .ok_or_else(|| Error::MessageType(message_type.clone()))?;
+            criteria.message_type = Some(message_type);
+        }
+
+        // A missing end of the range leaves it open
+        if config.time_start.is_some() || config.time_end.is_some() {
+            let start =
+                parse_timestamp(config.time_start.as_deref())?.unwrap_or(DateTime::<Utc>::MIN_UTC);
+            let end =
+                parse_timestamp(config.time_end.as_deref())?.unwrap_or(DateTime::<Utc>::MAX_UTC);
+            criteria.time_range = Some((start, end));
+        }
+
+        if let Some(pattern) = &config.text_pattern {
+            criteria = criteria.with_text_pattern(pattern)?;
+        }
+
+        Ok(criteria)
+    }
+}
+
+/// Parse an optional RFC 3339 timestamp
+fn parse_timestamp(value: Option<&str>) -> Result<Option<DateTime<Utc>>> {
+    value
+        .map(|value| {
+            DateTime::parse_from_rfc3339(value)
+                .map(|time| time.with_timezone(&Utc))
+                .map_err(|_| Error::Time(value.to_string()))
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use regex::Regex;
+
+    /// Convert criteria to their config form and back
+    fn round_trip(criteria: &FilterCriteria) -> FilterCriteria {
+        let config = FilterCriteriaConfig::from(criteria);
+        FilterCriteria::try_from(config).unwrap()
+    }
+
+    #[test]
+    fn round_trips_all_fields() {
+        let mut criteria = FilterCriteria::new()
+            .with_text_pattern("time(out)?")
+            .unwrap();
+        criteria.app_id = Some("APP1".to_string());
+        criteria.context_id = Some("CTX1".to_string());
+        criteria.log_level = Some(LogLevel::Warning);
+        criteria.min_log_level = Some(LogLevel::Error);
+        criteria.message_type = Some(MessageType::Control);
+        criteria.time_range = Some((
+            Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 2, 4, 0, 0).unwrap(),
+        ));
+        criteria.excluded_app_ids = vec!["SYS".to_string()];
+        criteria.excluded_context_ids = vec!["CTX2".to_string(), "CTX3".to_string()];
+        criteria.case_insensitive_ids = true;
+
+        let restored = round_trip(&criteria);
+        assert_eq!(restored.app_id, criteria.app_id);
+        assert_eq!(restored.context_id, criteria.context_id);
+        assert_eq!(restored.log_level, criteria.log_level);
+        assert_eq!(restored.min_log_level, criteria.min_log_level);
+        assert_eq!(restored.message_type, criteria.message_type);
+        assert_eq!(restored.time_range, criteria.time_range);
+        assert_eq!(
+            restored.text_pattern.as_ref().map(Regex::as_str),
+            Some("time(out)?")
+        );
+        assert_eq!(restored.excluded_app_ids, criteria.excluded_app_ids);
+        assert_eq!(restored.excluded_context_ids, criteria.excluded_context_ids);
+        assert!(restored.case_insensitive_ids);
+
+        // The config form survives serialization
+        let config = FilterCriteriaConfig::from(&criteria);
+        let toml = toml::to_string(&config).unwrap();
+        assert_eq!(
+            toml::from_str::<FilterCriteriaConfig>(&toml).unwrap(),
+            config
+        );
+    }
+
+    #[test]
+    fn round_trips_empty_criteria() {
+        let config = FilterCriteriaConfig::from(&FilterCriteria::new());
+        assert_eq!(config, FilterCriteriaConfig::default());
+        assert!(round_trip(&FilterCriteria::new()).is_empty());
+    }
+
+    #[test]
+    fn leaves_open_time_range_ends_out() {
+        let time = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+
+        let mut criteria = FilterCriteria::new();
+        criteria.time_range = Some((time, DateTime::<Utc>::MAX_UTC));
+        let config = FilterCriteriaConfig::from(&criteria);
+        assert!(config.time_start.is_some());
+        assert_eq!(config.time_end, None);
+        assert_eq!(round_trip(&criteria).time_range, criteria.time_range);
+
+        criteria.time_range = Some((DateTime::<Utc>::MIN_UTC, time));
+        let config = FilterCriteriaConfig::from(&criteria);
+        assert_eq!(config.time_start, None);
+        assert!(config.time_end.is_some());
+        assert_eq!(round_trip(&criteria).time_range, criteria.time_range);
+    }
+
+    #[test]
+    fn rejects_invalid_names() {
+        let config = FilterCriteriaConfig {
+            log_level: Some("loud".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            FilterCriteria::try_from(config),
+            Err(Error::LogLevel(name)) if name == "loud"
+        ));
+
+        let config = FilterCriteriaConfig {
+            min_log_level: Some("quiet".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            FilterCriteria::try_from(config),
+            Err(Error::LogLevel(_))
+        ));
+
+        let config = FilterCriteriaConfig {
+            message_type: Some("email".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            FilterCriteria::try_from(config),
+            Err(Error::MessageType(name)) if name == "email"
+        ));
+
+        let config = FilterCriteriaConfig {
+            time_start: Some("yesterday".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            FilterCriteria::try_from(config),
+            Err(Error::Time(_))
+        ));
+    }
+}
@@ -5,7 +5,7 @@
 use chrono::{DateTime, Utc};
 use regex::Regex;
 
-use crate::parser::{DltMessage, LogLevel, MessageType};
+use crate::parser::{DltMessage, LogLevel, MatchScope, MessageType};
 
 /// Filter criteria for DLT messages
 #[derive(Debug, Clone)]
@@ -16,12 +16,20 @@ pub struct FilterCriteria {
     pub context_id: Option<String>,
     /// Filter by log level
     pub log_level: Option<LogLevel>,
+    /// Show only messages at least as severe as this log level
+    pub min_log_level: Option<LogLevel>,
     /// Filter by time range
     pub time_range: Option<(DateTime<Utc>, DateTime<Utc>)>,
     /// Filter by message type
     pub message_type: Option<MessageType>,
-    /// Filter by text pattern
+    /// Filter by text pattern (payload text, application, context or ECU ID)
     pub text_pattern: Option<Regex>,
+    /// Hide messages of these application IDs
+    pub excluded_app_ids: Vec<String>,
+    /// Hide messages of these context IDs
+    pub excluded_context_ids: Vec<String>,
+    /// Compare application and context IDs ignoring ASCII case
+    pub case_insensitive_ids: bool,
 }
 
 impl Default for FilterCriteria {
@@ -30,9 +38,13 @@ impl Default for FilterCriteria {
             app_id: None,
             context_id: None,
             log_level: None,
+            min_log_level: None,
             time_range: None,
             message_type: None,
             text_pattern: None,
+            excluded_app_ids: Vec::new(),
+            excluded_context_ids: Vec::new(),
+            case_insensitive_ids: false,
         }
     }
 }
@@ -47,7 +59,11 @@ impl FilterCriteria {
     pub fn matches(&self, message: &DltMessage) -> bool {
         // Check application ID
         if let Some(app_id) = &self.app_id {
-            if message.app_id().as_ref().map_or(true, |id| id != app_id) {
+            if message
+                .app_id()
+                .as_ref()
+                .map_or(true, |id| !self.id_matches(id, app_id))
+            {
                 return false;
             }
         }
@@ -57,7 +73,28 @@ impl FilterCriteria {
             if message
                 .context_id()
                 .as_ref()
-                .map_or(true, |id| id != context_id)
+                .map_or(true, |id| !self.id_matches(id, context_id))
+            {
+                return false;
+            }
+        }
+
+        // Check excluded IDs
+        if let Some(app_id) = message.app_id() {
+            if self
+                .excluded_app_ids
+                .iter()
+                .any(|excluded| self.id_matches(&app_id, excluded))
+            {
+                return false;
+            }
+        }
+
+        if let Some(context_id) = message.context_id() {
+            if self
+                .excluded_context_ids
+                .iter()
+                .any(|excluded| self.id_matches(&context_id, excluded))
             {
                 return false;
             }
@@ -73,6 +110,16 @@ impl FilterCriteria {
             }
         }
 
+        // Check minimum log level
+        if let Some(min_level) = self.min_log_level {
+            if message
+                .log_level()
+                .is_none_or(|level| !level.is_at_least(min_level))
+            {
+                return false;
+            }
+        }
+
         // Check time range
         if let Some((start, end)) = &self.time_range {
             let timestamp = message.timestamp();
@@ -88,13 +135,9 @@ impl FilterCriteria {
             }
         }
 
-        // Check text pattern
+        // Check text pattern against the payload text (or hex dump) and the IDs
         if let Some(pattern) = &self.text_pattern {
-            if let Some(text) = &message.payload_text {
-                if !pattern.is_match(text) {
-                    return false;
-                }
-            } else {
+            if !message.matches_text(pattern, MatchScope::All) {
                 return false;
             }
         }
@@ -102,6 +145,15 @@ impl FilterCriteria {
         true
     }
 
+    /// Compare a message's ID with one of the filter, honouring `case_insensitive_ids`
+    fn id_matches(&self, id: &str, expected: &str) -> bool {
+        if self.case_insensitive_ids {
+            id.eq_ignore_ascii_case(expected)
+        } else {
+            id == expected
+        }
+    }
+
     /// Set the application ID filter
     pub fn with_app_id(mut self, app_id: impl Into<String>) -> Self {
         self.app_id = Some(app_id.into());
@@ -139,14 +191,38 @@ impl FilterCriteria {
         Ok(self)
     }
 
-    /// Clear all filters
+    /// Clear all filters (the ID comparison mode is kept)
     pub fn clear(&mut self) {
         self.app_id = None;
         self.context_id = None;
         self.log_level = None;
+        self.min_log_level = None;
         self.time_range = None;
         self.message_type = None;
         self.text_pattern = None;
+        self.clear_exclusions();
+    }
+
+    /// Exclude an application ID
+    pub fn exclude_app_id(&mut self, app_id: impl Into<String>) {
+        let app_id = app_id.into();
+        if !self.excluded_app_ids.contains(&app_id) {
+            self.excluded_app_ids.push(app_id);
+        }
+    }
+
+    /// Exclude a context ID
+    pub fn exclude_context_id(&mut self, context_id: impl Into<String>) {
+        let context_id = context_id.into();
+        if !self.excluded_context_ids.contains(&context_id) {
+            self.excluded_context_ids.push(context_id);
+        }
+    }
+
+    /// Clear the excluded application and context IDs
+    pub fn clear_exclusions(&mut self) {
+        self.excluded_app_ids.clear();
+        self.excluded_context_ids.clear();
     }
 
     /// Check if any filter is set
@@ -154,8 +230,11 @@ impl FilterCriteria {
         self.app_id.is_none()
             && self.context_id.is_none()
             && self.log_level.is_none()
+            && self.min_log_level.is_none()
             && self.time_range.is_none()
             && self.message_type.is_none()
             && self.text_pattern.is_none()
+            && self.excluded_app_ids.is_empty()
+            && self.excluded_context_ids.is_empty()
     }
 }
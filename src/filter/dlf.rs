@@ -0,0 +1,343 @@
+// DLF Filter Files
+//
+// This file loads filter files (.dlf) exported by the COVESA DLT Viewer. They
+// are XML documents with a flat list of <filter> entries, which are mapped to
+// filter criteria as far as our filters can express them.
+
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+
+use crate::filter::{Error, FilterCriteria, Result};
+use crate::parser::LogLevel;
+
+/// Kind of a DLF filter entry (the `<type>` element)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DlfFilterKind {
+    /// Show matching messages
+    Positive,
+    /// Hide matching messages
+    Negative,
+    /// Highlight matching messages
+    Marker,
+}
+
+/// A filter entry of a DLF file
+#[derive(Debug, Clone)]
+struct DlfFilter {
+    /// Name of the filter
+    name: String,
+    /// Whether the filter shows, hides or highlights messages
+    kind: DlfFilterKind,
+    /// Whether the filter is enabled
+    enabled: bool,
+    /// Application ID, if enabled
+    app_id: Option<String>,
+    /// Context ID, if enabled
+    context_id: Option<String>,
+    /// Most severe log level shown, if enabled
+    log_level_min: Option<LogLevel>,
+    /// Least severe log level shown, if enabled
+    log_level_max: Option<LogLevel>,
+    /// Whether the filter uses fields we can't apply (ECU ID, texts, ...)
+    has_unsupported_fields: bool,
+}
+
+impl DlfFilter {
+    /// Build a filter entry from the elements of a <filter> block
+    fn from_elements(elements: &[(String, String)]) -> Self {
+        let value = |name: &str| {
+            elements
+                .iter()
+                .find(|(tag, _)| tag == name)
+                .map(|(_, value)| value.as_str())
+        };
+        let enabled = |name: &str| value(name).is_some_and(|v| v.trim() == "1");
+        let field = |enable: &str, name: &str| {
+            value(name)
+                .filter(|_| enabled(enable))
+                .map(|v| v.trim().to_string())
+        };
+        let level = |enable: &str, name: &str| {
+            field(enable, name)
+                .and_then(|v| v.parse::<u8>().ok())
+                .map(LogLevel::from)
+        };
+
+        let has_unsupported_fields = [
+            "enableecuid",
+            "enableheadertext",
+            "enablepayloadtext",
+            "enablectrlmsgs",
+        ]
+        .iter()
+        .any(|name| enabled(name));
+
+        Self {
+            name: value("name").unwrap_or_default().to_string(),
+            kind: match value("type").map(str::trim) {
+                Some("1") => DlfFilterKind::Negative,
+                Some("2") => DlfFilterKind::Marker,
+                _ => DlfFilterKind::Positive,
+            },
+            enabled: value("enablefilter").is_none_or(|v| v.trim() == "1"),
+            app_id: field("enableapplicationid", "applicationid"),
+            context_id: field("enablecontextid", "contextid"),
+            log_level_min: level("enableloglevelmin", "loglevelmin"),
+            log_level_max: level("enableloglevelmax", "loglevelmax"),
+            has_unsupported_fields,
+        }
+    }
+
+    /// Get a name for the filter in warnings
+    fn label(&self) -> String {
+        if self.name.is_empty() {
+            "unnamed filter".to_string()
+        } else {
+            format!("filter '{}'", self.name)
+        }
+    }
+}
+
+/// Load filter criteria from a DLF file
+///
+/// Returns the criteria and warnings about filter entries that couldn't be
+/// applied completely.
+pub fn load_dlf(path: impl AsRef<Path>) -> Result<(FilterCriteria, Vec<String>)> {
+    let xml = fs::read_to_string(path)?;
+    parse_dlf(&xml)
+}
+
+/// Parse filter criteria from the contents of a DLF file
+///
+/// Negative filters on an application or context ID become exclusions. Marker
+/// filters are ignored. The DLT Viewer shows the messages matching any of the
+/// positive filters, which our criteria (combined with AND) can't express, so
+/// files with several enabled positive filters are rejected.
+pub fn parse_dlf(xml: &str) -> Result<(FilterCriteria, Vec<String>)> {
+    let filters: Vec<DlfFilter> = parse_filters(xml)
+        .into_iter()
+        .filter(|f| f.enabled)
+        .collect();
+    let positive = filters
+        .iter()
+        .filter(|f| f.kind == DlfFilterKind::Positive)
+        .count();
+    if positive > 1 {
+        return Err(Error::Dlf(format!(
+            "{} positive filters show the messages matching any of them, only one is supported",
+            positive
+        )));
+    }
+
+    let mut criteria = FilterCriteria::new();
+    let mut warnings = Vec::new();
+    for filter in &filters {
+        if filter.has_unsupported_fields {
+            warnings.push(format!("Ignoring unsupported fields of {}", filter.label()));
+        }
+
+        match filter.kind {
+            DlfFilterKind::Marker => {
+                warnings.push(format!("Ignoring marker {}", filter.label()));
+            }
+            DlfFilterKind::Negative => match (&filter.app_id, &filter.context_id) {
+                (Some(app_id), None) => criteria.exclude_app_id(app_id.clone()),
+                (None, Some(context_id)) => criteria.exclude_context_id(context_id.clone()),
+                _ => warnings.push(format!(
+                    "Ignoring negative {}: only an application or a context ID can be hidden",
+                    filter.label()
+                )),
+            },
+            DlfFilterKind::Positive => {
+                criteria.app_id = filter.app_id.clone();
+                criteria.context_id = filter.context_id.clone();
+
+                // Our log level filters match a single level, or every level
+                // at least as severe as a given one
+                match (filter.log_level_min, filter.log_level_max) {
+                    (None, None) => {}
+                    (Some(min), Some(max)) if min == max => criteria.log_level = Some(min),
+                    (None | Some(LogLevel::Fatal), Some(max)) => criteria.min_log_level = Some(max),
+                    _ => warnings.push(format!(
+                        "Ignoring the log level range of {}: only a single level or a range \
+                         from fatal is supported",
+                        filter.label()
+                    )),
+                }
+            }
+        }
+    }
+
+    Ok((criteria, warnings))
+}
+
+/// Extract the filter entries from the XML document
+///
+/// DLF files are flat, so the elements inside each <filter> block are simply
+/// collected as (tag, text) pairs rather than using a full XML parser.
+fn parse_filters(xml: &str) -> Vec<DlfFilter> {
+    let block = Regex::new(r"(?s)<filter>(.*?)</filter>").unwrap();
+    let element = Regex::new(r"<(\w+)>([^<]*)</(\w+)>").unwrap();
+
+    block
+        .captures_iter(xml)
+        .map(|block| {
+            let elements: Vec<(String, String)> = element
+                .captures_iter(&block[1])
+                .filter(|e| e[1] == e[3])
+                .map(|e| (e[1].to_string(), unescape(&e[2])))
+                .collect();
+            DlfFilter::from_elements(&elements)
+        })
+        .collect()
+}
+
+/// Replace the predefined XML entities
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a DLF document from the elements of each filter
+    fn dlf(filters: &[&[(&str, &str)]]) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<dltfilter>\n");
+        for elements in filters {
+            xml.push_str("    <filter>\n");
+            for (tag, value) in *elements {
+                xml.push_str(&format!("        <{tag}>{value}</{tag}>\n"));
+            }
+            xml.push_str("    </filter>\n");
+        }
+        xml.push_str("</dltfilter>\n");
+        xml
+    }
+
+    #[test]
+    fn negative_filters_become_exclusions() {
+        let xml = dlf(&[
+            &[
+                ("type", "1"),
+                ("applicationid", "APP1"),
+                ("enableapplicationid", "1"),
+            ],
+            &[
+                ("type", "1"),
+                ("contextid", "CTX1"),
+                ("enablecontextid", "1"),
+            ],
+            &[
+                ("type", "1"),
+                ("name", "both"),
+                ("applicationid", "APP2"),
+                ("enableapplicationid", "1"),
+                ("contextid", "CTX2"),
+                ("enablecontextid", "1"),
+            ],
+        ]);
+
+        let (criteria, warnings) = parse_dlf(&xml).unwrap();
+        assert_eq!(criteria.excluded_app_ids, ["APP1"]);
+        assert_eq!(criteria.excluded_context_ids, ["CTX1"]);
+        assert_eq!(criteria.app_id, None);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("filter 'both'"));
+    }
+
+    #[test]
+    fn single_positive_filter_sets_ids_and_level() {
+        let xml = dlf(&[&[
+            ("type", "0"),
+            ("applicationid", "APP1"),
+            ("enableapplicationid", "1"),
+            ("contextid", "CTX1"),
+            ("enablecontextid", "1"),
+            ("loglevelmin", "2"),
+            ("enableloglevelmin", "1"),
+            ("loglevelmax", "2"),
+            ("enableloglevelmax", "1"),
+        ]]);
+
+        let (criteria, warnings) = parse_dlf(&xml).unwrap();
+        assert_eq!(criteria.app_id.as_deref(), Some("APP1"));
+        assert_eq!(criteria.context_id.as_deref(), Some("CTX1"));
+        assert_eq!(criteria.log_level, Some(LogLevel::Error));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn level_ranges_from_fatal_set_the_minimum_level() {
+        for min in [None, Some("1")] {
+            let mut fields = vec![("loglevelmax", "4"), ("enableloglevelmax", "1")];
+            if let Some(min) = min {
+                fields.extend([("loglevelmin", min), ("enableloglevelmin", "1")]);
+            }
+
+            let (criteria, warnings) = parse_dlf(&dlf(&[&fields])).unwrap();
+            assert_eq!(criteria.min_log_level, Some(LogLevel::Info));
+            assert_eq!(criteria.log_level, None);
+            assert!(warnings.is_empty());
+        }
+    }
+
+    #[test]
+    fn other_level_ranges_are_ignored() {
+        let xml = dlf(&[&[
+            ("loglevelmin", "2"),
+            ("enableloglevelmin", "1"),
+            ("loglevelmax", "4"),
+            ("enableloglevelmax", "1"),
+        ]]);
+
+        let (criteria, warnings) = parse_dlf(&xml).unwrap();
+        assert_eq!(criteria.min_log_level, None);
+        assert_eq!(criteria.log_level, None);
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn several_positive_filters_are_rejected() {
+        let xml = dlf(&[
+            &[("applicationid", "APP1"), ("enableapplicationid", "1")],
+            &[("applicationid", "APP2"), ("enableapplicationid", "1")],
+        ]);
+
+        assert!(matches!(parse_dlf(&xml), Err(Error::Dlf(_))));
+    }
+
+    #[test]
+    fn disabled_filters_are_skipped() {
+        let xml = dlf(&[
+            &[("applicationid", "APP1"), ("enableapplicationid", "1")],
+            &[
+                ("applicationid", "APP2"),
+                ("enableapplicationid", "1"),
+                ("enablefilter", "0"),
+            ],
+        ]);
+
+        let (criteria, _) = parse_dlf(&xml).unwrap();
+        assert_eq!(criteria.app_id.as_deref(), Some("APP1"));
+    }
+
+    #[test]
+    fn entities_are_unescaped() {
+        let xml = dlf(&[&[
+            ("type", "2"),
+            ("name", "&lt;a&gt; &amp; &quot;b&quot; &apos;c&apos;"),
+        ]]);
+
+        let (_, warnings) = parse_dlf(&xml).unwrap();
+        assert_eq!(warnings, ["Ignoring marker filter '<a> & \"b\" 'c''"]);
+
+        // An escaped ampersand isn't unescaped twice
+        assert_eq!(unescape("&amp;lt;"), "&lt;");
+    }
+}
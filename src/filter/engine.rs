@@ -30,7 +30,7 @@ impl FilterEngine {
         // Apply the filter in parallel
         (0..file.message_count())
             .into_par_iter()
-            .filter_map(|idx| match file.get_message(idx) {
+            .filter_map(|idx| match file.parse_message(idx) {
                 Ok(msg) if self.matches(&msg) => Some(idx),
                 _ => None,
             })
@@ -2,8 +2,40 @@
 //
 // This module handles filtering of DLT messages.
 
+mod command;
+mod config;
 mod criteria;
+mod dlf;
 mod engine;
 
+pub use command::parse_time_of_day;
+pub use config::FilterCriteriaConfig;
 pub use criteria::FilterCriteria;
+pub use dlf::load_dlf;
 pub use engine::FilterEngine;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Invalid pattern: {0}")]
+    Pattern(#[from] regex::Error),
+
+    #[error("Unknown log level: {0}")]
+    LogLevel(String),
+
+    #[error("Unknown message type: {0}")]
+    MessageType(String),
+
+    #[error("Unknown filter clause: {0}")]
+    Clause(String),
+
+    #[error("Invalid time: {0}")]
+    Time(String),
+
+    #[error("Unsupported filter file: {0}")]
+    Dlf(String),
+
+    #[error("Failed to read filter file: {0}")]
+    Io(#[from] std::io::Error),
+}
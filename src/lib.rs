@@ -0,0 +1,83 @@
+//! DLT Log Viewer library
+//!
+//! The parser, filter and search engines behind the `dltui` viewer, usable
+//! without the terminal interface.
+//!
+//! # Examples
+//!
+//! Open a DLT file and print its messages:
+//!
+//! ```no_run
+//! use dltui::parser::DltFile;
+//!
+//! let file = DltFile::open("trace.dlt")?;
+//! for message in file.iter() {
+//!     let message = message?;
+//!     println!(
+//!         "{} {} {}",
+//!         message.timestamp(),
+//!         message.app_id().unwrap_or_default(),
+//!         message.payload_as_text()
+//!     );
+//! }
+//! # Ok::<(), dltui::parser::Error>(())
+//! ```
+//!
+//! Find the warnings and errors that mention a pattern:
+//!
+//! ```no_run
+//! use dltui::filter::{FilterCriteria, FilterEngine};
+//! use dltui::parser::{DltFile, LogLevel};
+//! use dltui::search::SearchEngine;
+//!
+//! let file = DltFile::open("trace.dlt")?;
+//!
+//! let criteria = FilterCriteria {
+//!     min_log_level: Some(LogLevel::Warning),
+//!     ..FilterCriteria::default()
+//! };
+//! let warnings = FilterEngine::new(criteria).apply(&file);
+//!
+//! let search = SearchEngine::new("timeout").expect("valid pattern");
+//! for idx in search.search_in_indices(&file, &warnings) {
+//!     println!("{}", file.get_message(idx)?.payload_as_text());
+//! }
+//! # Ok::<(), dltui::parser::Error>(())
+//! ```
+//!
+//! Decode a custom payload format for the messages of one application:
+//!
+//! ```no_run
+//! use dltui::parser::{DltFile, DltMessage, PayloadDecoder, PayloadDecoders};
+//!
+//! struct TlvDecoder;
+//!
+//! impl PayloadDecoder for TlvDecoder {
+//!     fn decode(&self, msg: &DltMessage) -> Option<String> {
+//!         let (&tag, value) = msg.payload.split_first()?;
+//!         Some(format!("tag {}: {:02x?}", tag, value))
+//!     }
+//! }
+//!
+//! let mut decoders = PayloadDecoders::with_builtins();
+//! decoders.register(Some("NAV"), None, TlvDecoder);
+//!
+//! let file = DltFile::open("trace.dlt")?;
+//! for message in file.iter() {
+//!     let message = message?;
+//!     let text = decoders
+//!         .decode(&message)
+//!         .unwrap_or_else(|| message.payload_as_text());
+//!     println!("{}", text);
+//! }
+//! # Ok::<(), dltui::parser::Error>(())
+//! ```
+
+pub mod config;
+pub mod export;
+pub mod filter;
+pub mod parser;
+pub mod search;
+
+#[cfg(test)]
+mod testing;
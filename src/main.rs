@@ -3,29 +3,33 @@
 //! A TUI tool for viewing and analyzing Covesa DLT log files.
 
 mod app;
-mod config;
-mod filter;
-mod parser;
-mod search;
 mod ui;
 
-use std::io;
+// Not every message builder is used by the viewer's tests
+#[cfg(test)]
+#[allow(dead_code)]
+mod testing;
+
+// The core lives in the library; importing its modules here lets the
+// interface modules refer to them as `crate::parser` etc.
+use dltui::{config, export, filter, parser, search};
+
+use std::io::{self, BufWriter, Write};
 use std::path::PathBuf;
+use std::thread;
 use std::time::Duration;
 
 use anyhow::{Context, Result};
 use clap::Parser;
-use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture, KeyCode, KeyModifiers},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-use ratatui::{backend::CrosstermBackend, Terminal};
-
-use crate::app::{App, InputMode};
-use crate::config::Settings;
-use crate::filter::FilterCriteria;
-use crate::ui::{Event, EventHandler};
+use crossterm::event::{KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{layout::Rect, Terminal};
+
+use crate::app::{App, FileSort, InputMode, KeyMap, ViewMode};
+use crate::config::{History, Session, Settings, Timezone};
+use crate::export::{write_messages, OutputFormat};
+use crate::filter::{load_dlf, FilterCriteria};
+use crate::parser::{DltMessage, LogLevel};
+use crate::ui::{Event, EventHandler, TerminalGuard, Theme};
 
 /// Command line arguments
 #[derive(Parser, Debug)]
@@ -43,9 +47,52 @@ struct Args {
     #[clap(short, long)]
     search: Option<String>,
 
+    /// Select the message at this position (1-based) in the list
+    #[clap(long, value_name = "N")]
+    goto: Option<usize>,
+
+    /// Select the first message at or after this time of day (HH:MM[:SS[.fff]])
+    #[clap(long, value_name = "TIME", conflicts_with = "goto")]
+    goto_time: Option<String>,
+
+    /// Print the matching messages to stdout and exit, without starting the TUI
+    #[clap(long)]
+    print: bool,
+
+    /// Output format for --print
+    #[clap(long, value_enum, default_value_t = OutputFormat::Text, requires = "print")]
+    format: OutputFormat,
+
     /// Config file
     #[clap(short, long)]
     config: Option<PathBuf>,
+
+    /// Interval in milliseconds between checks for file changes in follow mode
+    /// (overrides `tick_rate` in the config file)
+    #[clap(long, value_name = "MS")]
+    tick_rate: Option<u64>,
+
+    /// Color theme, `default` or `light` (overrides `theme` in the config file)
+    #[clap(long, value_name = "NAME")]
+    theme: Option<String>,
+
+    /// Don't capture the mouse, so text can be selected in the terminal
+    /// (overrides `mouse` in the config file)
+    #[clap(long)]
+    no_mouse: bool,
+
+    /// Index at most N messages from the start of each file, for a quick look
+    /// at large files
+    #[clap(long, value_name = "N")]
+    limit: Option<usize>,
+
+    /// DLT Viewer filter file (.dlf) to apply instead of the default filter
+    #[clap(long, value_name = "PATH")]
+    dlf: Option<PathBuf>,
+
+    /// Reopen the files, selections and filter of the last session
+    #[clap(long, conflicts_with_all = ["FILE", "print"])]
+    resume: bool,
 }
 
 fn main() -> Result<()> {
@@ -53,142 +100,373 @@ fn main() -> Result<()> {
     let args = Args::parse();
 
     // Load settings
-    let settings = if let Some(config_path) = args.config {
+    let mut settings = if let Some(config_path) = &args.config {
         Settings::load(config_path).unwrap_or_default()
     } else {
         Settings::load_default()
     };
 
-    // Setup terminal
-    enable_raw_mode().context("Failed to enable raw mode")?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)
-        .context("Failed to enter alternate screen")?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend).context("Failed to create terminal")?;
+    // Build the default filters before the terminal is taken over, so any
+    // warnings end up on the normal screen
+    for warning in settings.validate_timestamp_formats() {
+        eprintln!("{}", warning);
+    }
+    let mut defaults = match &args.dlf {
+        Some(path) => {
+            let (criteria, warnings) = load_dlf(path)
+                .with_context(|| format!("Failed to load filter file {}", path.display()))?;
+            for warning in warnings {
+                eprintln!("{}", warning);
+            }
+            criteria
+        }
+        None => default_filter(&settings),
+    };
+    defaults.case_insensitive_ids = settings.case_insensitive_ids;
+    let mut keymap = KeyMap::default();
+    for warning in keymap.apply_overrides(&settings.keys) {
+        eprintln!("{}", warning);
+    }
+    let timezone = Timezone::parse(&settings.timezone).unwrap_or_else(|| {
+        eprintln!("Ignoring unknown timezone '{}'", settings.timezone);
+        Timezone::Utc
+    });
+
+    let theme_name = args.theme.as_deref().unwrap_or(&settings.theme);
+    let theme = Theme::from_name(theme_name).unwrap_or_else(|| {
+        eprintln!(
+            "Unknown theme '{}' (expected one of: {}), using the default theme",
+            theme_name,
+            Theme::NAMES.join(", ")
+        );
+        Theme::default()
+    });
+
+    let file_sort = FileSort::from_name(&settings.file_sort).unwrap_or_else(|| {
+        eprintln!(
+            "Unknown file_sort '{}' (expected one of: {}), keeping the load order",
+            settings.file_sort,
+            FileSort::NAMES.join(", ")
+        );
+        FileSort::Load
+    });
+
+    // In headless mode, print the messages instead of starting the TUI
+    if args.print {
+        return print_messages(&args, settings, defaults, timezone);
+    }
+
+    // Load the last session to resume, before the terminal is taken over
+    let session = if args.resume {
+        Some(Session::load_default().context("Failed to load the last session")?)
+    } else {
+        None
+    };
+    let files: Vec<PathBuf> = match &session {
+        Some(session) => session.files.iter().map(|file| file.path.clone()).collect(),
+        None => args.files.clone(),
+    };
+
+    // Setup terminal; it is restored when the guard is dropped, even on a panic
+    let mouse = settings.mouse && !args.no_mouse;
+    let mut terminal = TerminalGuard::new(mouse).context("Failed to set up terminal")?;
 
     // Create app state
-    let mut app = App::new();
+    let mut app = App::new(settings);
+    app.history = History::load_default();
+    app.timezone = timezone;
+    app.theme = theme;
+    app.message_limit = args.limit;
+    app.file_sort = file_sort;
+    app.keymap = keymap;
 
-    // Load files
-    for path in &args.files {
-        if let Err(e) = app.load_file(path.clone()) {
-            eprintln!("Error loading file {}: {}", path.display(), e);
-        }
+    // Create event handler
+    let tick_rate = Duration::from_millis(args.tick_rate.unwrap_or(app.settings.tick_rate));
+    let event_handler = EventHandler::new(tick_rate);
+    app.events = Some(event_handler.sender());
+
+    // Load files in the background, so the UI is shown right away
+    for path in &files {
+        app.start_loading(path.clone());
+
+        let path = path.clone();
+        let cache_size = app.settings.message_cache_size;
+        let limit = app.message_limit;
+        let sender = event_handler.sender();
+        thread::spawn(move || {
+            let result = App::open_file_with_progress(&path, cache_size, limit, |progress| {
+                let _ = sender.send(Event::LoadProgress(path.clone(), progress));
+            });
+            let _ = sender.send(Event::FileLoaded(path, result));
+        });
     }
 
-    // Apply filter if specified
-    if let Some(filter_str) = args.filter {
-        if let Err(e) = app.apply_text_filter(&filter_str) {
-            eprintln!("Error applying filter pattern: {}", e);
-        }
+    // Let the user pick a file if none were given
+    if files.is_empty() {
+        app.open_picker();
     }
 
-    // Apply search if specified
-    if let Some(search_str) = args.search {
-        if let Err(e) = app.search(&search_str) {
-            eprintln!("Error applying search pattern: {}", e);
-        }
+    // Apply default filters from the config
+    if !defaults.is_empty() {
+        app.set_filter(defaults);
     }
 
-    // Create event handler
-    let tick_rate = Duration::from_millis(settings.tick_rate);
-    let event_handler = EventHandler::new(tick_rate);
+    // The filter, search and jump from the command line are applied once the files are loaded
+    let mut startup = StartupActions {
+        session,
+        filter: args.filter,
+        search: args.search,
+        goto: args.goto,
+        goto_time: args.goto_time,
+    };
+    if app.loading.is_empty() {
+        startup.apply(&mut app);
+    }
 
     // Run the main loop
-    run_app(&mut terminal, app, event_handler)?;
+    run_app(&mut terminal, &mut app, event_handler, startup)?;
 
     // Restore terminal
-    disable_raw_mode().context("Failed to disable raw mode")?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )
-    .context("Failed to leave alternate screen")?;
-    terminal.show_cursor().context("Failed to show cursor")?;
+    drop(terminal);
+
+    // Save the history, including the updated recent files list. The settings
+    // are left alone, as the user edits them.
+    if let Err(e) = app.history.save_default() {
+        eprintln!("Error saving history: {}", e);
+    }
+    if !app.files.is_empty() {
+        if let Err(e) = app.session().save_default() {
+            eprintln!("Error saving session: {}", e);
+        }
+    }
 
     Ok(())
 }
 
+/// Build the default filter criteria from the settings
+///
+/// An unknown log level is ignored with a warning, keeping the other defaults.
+fn default_filter(settings: &Settings) -> FilterCriteria {
+    let log_level = settings.default_log_level.as_deref().and_then(|name| {
+        let level = LogLevel::from_name(name);
+        if level.is_none() {
+            eprintln!("Ignoring unknown default log level: {}", name);
+        }
+        level
+    });
+
+    FilterCriteria {
+        app_id: settings.default_app_id.clone(),
+        context_id: settings.default_context_id.clone(),
+        log_level,
+        ..FilterCriteria::new()
+    }
+}
+
+/// Print the messages matching the filter (and search) to stdout
+fn print_messages(
+    args: &Args,
+    settings: Settings,
+    defaults: FilterCriteria,
+    timezone: Timezone,
+) -> Result<()> {
+    let mut app = App::new(settings);
+    app.timezone = timezone;
+    app.message_limit = args.limit;
+
+    // Load the files, merging them in time order if there are several
+    for path in &args.files {
+        app.load_file(path.clone())
+            .with_context(|| format!("Failed to load {}", path.display()))?;
+    }
+    if app.files.len() > 1 {
+        app.toggle_merged();
+    }
+
+    // Apply the filters, then the search (which runs synchronously without an event loop)
+    if !defaults.is_empty() {
+        app.set_filter(defaults);
+    }
+    if let Some(filter) = &args.filter {
+        app.apply_filter_command(filter)
+            .context("Error applying filter")?;
+    }
+    let positions: Vec<usize> = match &args.search {
+        Some(pattern) => {
+            app.search(pattern)
+                .context("Error applying search pattern")?;
+            app.search_result_positions()
+        }
+        None => (0..app.filtered_messages.len()).collect(),
+    };
+
+    // A single unfiltered file is streamed in order, otherwise the matches are looked up
+    let messages: Box<dyn Iterator<Item = DltMessage> + '_> =
+        if app.files.len() == 1 && app.filter.is_empty() && args.search.is_none() {
+            Box::new(app.files[0].iter().filter_map(|msg| msg.ok()))
+        } else {
+            Box::new(positions.into_iter().filter_map(|pos| app.message_at(pos)))
+        };
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    let written = write_messages(
+        &mut out,
+        messages,
+        args.format,
+        app.timezone,
+        &app.settings.list_timestamp_format,
+    )
+    .and_then(|_| out.flush());
+
+    // Stop quietly when the reader goes away, e.g. when piped into `head`
+    match written {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+/// Session to resume, and filter, search and jump given on the command line
+struct StartupActions {
+    /// Session to restore (`--resume`)
+    session: Option<Session>,
+    /// Filter command (`--filter`)
+    filter: Option<String>,
+    /// Search pattern (`--search`)
+    search: Option<String>,
+    /// Position to select (`--goto`)
+    goto: Option<usize>,
+    /// Time of day to select (`--goto-time`)
+    goto_time: Option<String>,
+}
+
+impl StartupActions {
+    /// Apply the session, filter, search and jump, reporting errors in the status bar
+    fn apply(&mut self, app: &mut App) {
+        if let Some(session) = self.session.take() {
+            app.restore_session(&session);
+        }
+
+        if let Some(filter_str) = self.filter.take() {
+            if let Err(e) = app.apply_filter_command(&filter_str) {
+                app.status_message = format!("Error applying filter: {}", e);
+            }
+        }
+
+        if let Some(search_str) = self.search.take() {
+            if let Err(e) = app.search(&search_str) {
+                app.status_message = e.to_string();
+            }
+        }
+
+        // Jump last, so the position isn't replaced by the first search result
+        if let Some(number) = self.goto.take() {
+            app.goto_message(number);
+        }
+        if let Some(time) = self.goto_time.take() {
+            if let Err(e) = app.goto_time(&time) {
+                app.status_message = format!("Error jumping to time: {}", e);
+            }
+        }
+    }
+}
+
 /// Run the application
 fn run_app<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
-    mut app: App,
+    app: &mut App,
     event_handler: EventHandler,
+    mut startup: StartupActions,
 ) -> Result<()> {
+    app.set_viewport_height(ui::log_list_area(app, terminal.size()?).height as usize);
+
+    // The UI is only redrawn after events that may have changed it, so an idle
+    // session doesn't redraw on every tick
+    let mut dirty = true;
+
     loop {
         // Draw the UI
-        terminal.draw(|f| ui::render(f, &app))?;
+        if dirty {
+            terminal.draw(|f| ui::render(f, app))?;
+        }
 
         // Handle events
-        match event_handler.next()? {
+        let event = event_handler.next()?;
+        dirty = !matches!(event, Event::Tick);
+        match event {
+            Event::Key(key)
+                if key.code == KeyCode::Char('c') && key.modifiers == KeyModifiers::CONTROL =>
+            {
+                // Ctrl+C quits from any mode
+                app.exit();
+            }
             Event::Key(key) => {
                 // Handle keys based on input mode
                 match app.input_mode {
-                    InputMode::Normal => match key.code {
-                        // Quit
+                    InputMode::Normal if app.view_mode == ViewMode::Picker => match key.code {
                         KeyCode::Char('q') => {
                             app.exit();
                         }
-                        KeyCode::Char('c') if key.modifiers == KeyModifiers::CONTROL => {
-                            app.exit();
+                        KeyCode::Esc => {
+                            app.close_picker();
                         }
-
-                        // Navigation
                         KeyCode::Up | KeyCode::Char('k') => {
-                            app.move_up();
+                            if let Some(picker) = &mut app.picker {
+                                picker.move_up();
+                            }
                         }
                         KeyCode::Down | KeyCode::Char('j') => {
-                            app.move_down();
-                        }
-                        KeyCode::Home | KeyCode::Char('g') => {
-                            app.move_to_top();
-                        }
-                        KeyCode::End | KeyCode::Char('G') => {
-                            app.move_to_bottom();
-                        }
-
-                        // View controls
-                        KeyCode::Enter => {
-                            app.toggle_view_mode();
-                        }
-                        KeyCode::Char('h') | KeyCode::Char('?') => {
-                            app.show_help();
+                            if let Some(picker) = &mut app.picker {
+                                picker.move_down();
+                            }
                         }
-
-                        // File navigation
-                        KeyCode::Char('p') => {
-                            app.prev_file();
+                        KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') => {
+                            if let Some(picker) = &mut app.picker {
+                                picker.parent_dir();
+                            }
                         }
-
-                        // Search
-                        KeyCode::Char('/') => {
-                            app.enter_search_mode();
+                        KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => {
+                            app.picker_open_selected();
                         }
-                        KeyCode::Char('n') => {
-                            app.next_search_result();
+                        _ => {}
+                    },
+                    InputMode::Normal if app.view_mode == ViewMode::Histogram => match key.code {
+                        KeyCode::Char('q') => {
+                            app.exit();
                         }
-                        KeyCode::Char('N') => {
-                            app.prev_search_result();
+                        KeyCode::Esc | KeyCode::Char('t') => {
+                            app.close_histogram();
                         }
-
-                        // Filter
-                        KeyCode::Char('f') => {
-                            app.enter_filter_mode();
+                        KeyCode::Left | KeyCode::Char('h') => {
+                            if let Some(histogram) = &mut app.histogram {
+                                histogram.move_left();
+                            }
                         }
-
-                        // Toggle case sensitivity for search
-                        KeyCode::Char('i') => {
-                            if let Err(e) = app.toggle_case_sensitivity() {
-                                app.status_message =
-                                    format!("Error toggling case sensitivity: {}", e);
+                        KeyCode::Right | KeyCode::Char('l') => {
+                            if let Some(histogram) = &mut app.histogram {
+                                histogram.move_right();
                             }
                         }
-
-                        // Other keys
+                        KeyCode::Enter => {
+                            app.histogram_jump();
+                        }
                         _ => {}
                     },
+                    InputMode::Normal if key.code == KeyCode::Esc && app.is_searching() => {
+                        app.cancel_search();
+                    }
+                    InputMode::Normal if key.code == KeyCode::Esc && app.is_exporting() => {
+                        app.cancel_export();
+                    }
+                    InputMode::Normal
+                        if key.code == KeyCode::Esc && app.context_radius().is_some() =>
+                    {
+                        app.close_context();
+                    }
+                    InputMode::Normal => {
+                        if let Some(action) = app.keymap.action(key) {
+                            app.perform(action);
+                        }
+                    }
                     InputMode::Search => {
                         // Handle search input
                         if let KeyCode::Char(c) = key.code {
@@ -198,6 +476,8 @@ fn run_app<B: ratatui::backend::Backend>(
                                 KeyCode::Enter => app.handle_search_input('\n'),
                                 KeyCode::Backspace => app.handle_search_input('\u{8}'),
                                 KeyCode::Esc => app.handle_search_input('\u{1b}'),
+                                KeyCode::Up => app.history_prev(),
+                                KeyCode::Down => app.history_next(),
                                 _ => {}
                             }
                         }
@@ -211,14 +491,64 @@ fn run_app<B: ratatui::backend::Backend>(
                                 KeyCode::Enter => app.handle_filter_input('\n'),
                                 KeyCode::Backspace => app.handle_filter_input('\u{8}'),
                                 KeyCode::Esc => app.handle_filter_input('\u{1b}'),
+                                KeyCode::Up => app.history_prev(),
+                                KeyCode::Down => app.history_next(),
+                                _ => {}
+                            }
+                        }
+                    }
+                    InputMode::Command => {
+                        // Handle command input
+                        if let KeyCode::Char(c) = key.code {
+                            app.handle_command_input(c);
+                        } else {
+                            match key.code {
+                                KeyCode::Enter => app.handle_command_input('\n'),
+                                KeyCode::Backspace => app.handle_command_input('\u{8}'),
+                                KeyCode::Esc => app.handle_command_input('\u{1b}'),
+                                KeyCode::Up => app.history_prev(),
+                                KeyCode::Down => app.history_next(),
                                 _ => {}
                             }
                         }
                     }
                 }
             }
-            Event::Resize(_, _) => {}
-            Event::Tick => {}
+            Event::LoadProgress(path, progress) => {
+                app.update_loading_progress(&path, progress);
+            }
+            Event::FileLoaded(path, result) => {
+                app.finish_loading(path, result);
+                if app.loading.is_empty() {
+                    startup.apply(app);
+                }
+            }
+            Event::SearchProgress(id, results, progress) => {
+                app.add_search_results(id, results, progress);
+            }
+            Event::SearchFinished(id) => {
+                app.finish_search(id);
+            }
+            Event::ExportProgress(id, written) => {
+                app.update_export_progress(id, written);
+            }
+            Event::ExportFinished(id, result) => {
+                app.finish_export(id, result);
+            }
+            Event::Mouse(mouse) => {
+                handle_mouse(app, mouse, terminal.size()?);
+            }
+            Event::Resize(width, height) => {
+                // The next iteration redraws at the new size
+                app.set_viewport_height(
+                    ui::log_list_area(app, Rect::new(0, 0, width, height)).height as usize,
+                );
+            }
+            Event::Tick => {
+                dirty = app.check_file_changes()
+                    | app.poll_follow()
+                    | app.continue_incremental_search();
+            }
         }
 
         // Check if we should exit
@@ -227,5 +557,35 @@ fn run_app<B: ratatui::backend::Backend>(
         }
     }
 
+    // Don't leave a partial file behind
+    app.cancel_export();
+
     Ok(())
 }
+
+/// Handle a mouse event
+fn handle_mouse(app: &mut App, mouse: MouseEvent, size: Rect) {
+    match mouse.kind {
+        MouseEventKind::ScrollUp => match &mut app.picker {
+            Some(picker) => picker.move_up(),
+            None => app.move_up(),
+        },
+        MouseEventKind::ScrollDown => match &mut app.picker {
+            Some(picker) => picker.move_down(),
+            None => app.move_down(),
+        },
+        MouseEventKind::Down(MouseButton::Left) if app.view_mode == ViewMode::List => {
+            // Select the clicked row of the log list
+            let area = ui::log_list_area(app, size);
+            if mouse.column >= area.x
+                && mouse.column < area.x + area.width
+                && mouse.row >= area.y
+                && mouse.row < area.y + area.height
+            {
+                let row = (mouse.row - area.y) as usize;
+                app.select_visible_row(row, area.height as usize);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,117 @@
+// Payload Decoders
+//
+// This file defines the extension point for decoding custom payload formats
+// (e.g. protobuf or TLV) into text, and a registry that tries decoders in order
+// for the messages of given application and context IDs.
+
+use super::dlt_message::DltMessage;
+
+/// Decoder turning the payload of a message into text
+pub trait PayloadDecoder: Send + Sync {
+    /// Decode the payload of a message, or return `None` if the payload is not
+    /// in the format of this decoder
+    fn decode(&self, msg: &DltMessage) -> Option<String>;
+}
+
+/// Decoder showing payloads that are valid UTF-8 as text
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Utf8Decoder;
+
+impl PayloadDecoder for Utf8Decoder {
+    fn decode(&self, msg: &DltMessage) -> Option<String> {
+        let text = std::str::from_utf8(&msg.payload).ok()?;
+        // Control characters other than line breaks and tabs mean binary data
+        text.chars()
+            .all(|c| !c.is_control() || matches!(c, '\n' | '\r' | '\t'))
+            .then(|| text.to_string())
+    }
+}
+
+/// Decoder pretty-printing printable payloads that are JSON objects or arrays
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JsonDecoder;
+
+impl PayloadDecoder for JsonDecoder {
+    fn decode(&self, msg: &DltMessage) -> Option<String> {
+        let text = msg.payload_text.as_deref()?.trim();
+        if !(text.starts_with('{') || text.starts_with('[')) {
+            return None;
+        }
+        let value: serde_json::Value = serde_json::from_str(text).ok()?;
+        serde_json::to_string_pretty(&value).ok()
+    }
+}
+
+/// Registered decoder with the IDs of the messages it applies to
+struct Registration {
+    app_id: Option<String>,
+    context_id: Option<String>,
+    decoder: Box<dyn PayloadDecoder>,
+}
+
+impl Registration {
+    /// Check whether the decoder applies to a message
+    fn applies_to(&self, msg: &DltMessage) -> bool {
+        let matches = |id: &Option<String>, msg_id: Option<String>| {
+            id.is_none() || id.as_deref() == msg_id.as_deref()
+        };
+        matches(&self.app_id, msg.app_id()) && matches(&self.context_id, msg.context_id())
+    }
+}
+
+/// Registry of payload decoders, tried in registration order
+#[derive(Default)]
+pub struct PayloadDecoders {
+    registrations: Vec<Registration>,
+}
+
+impl PayloadDecoders {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry with the built-in decoders: JSON, then UTF-8
+    pub fn with_builtins() -> Self {
+        let mut decoders = Self::new();
+        decoders.register(None, None, JsonDecoder);
+        decoders.register(None, None, Utf8Decoder);
+        decoders
+    }
+
+    /// Register a decoder for the messages with the given application and context
+    /// IDs, where `None` matches any ID
+    ///
+    /// Decoders registered earlier are tried first.
+    pub fn register(
+        &mut self,
+        app_id: Option<&str>,
+        context_id: Option<&str>,
+        decoder: impl PayloadDecoder + 'static,
+    ) {
+        self.registrations.push(Registration {
+            app_id: app_id.map(str::to_string),
+            context_id: context_id.map(str::to_string),
+            decoder: Box::new(decoder),
+        });
+    }
+
+    /// Decode the payload of a message with the first decoder that applies to it
+    /// and accepts the payload
+    pub fn decode(&self, msg: &DltMessage) -> Option<String> {
+        self.registrations
+            .iter()
+            .filter(|registration| registration.applies_to(msg))
+            .find_map(|registration| registration.decoder.decode(msg))
+    }
+
+    /// Get the number of registered decoders
+    pub fn len(&self) -> usize {
+        self.registrations.len()
+    }
+
+    /// Check whether no decoders are registered
+    pub fn is_empty(&self) -> bool {
+        self.registrations.is_empty()
+    }
+}
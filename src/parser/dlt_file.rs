@@ -1,78 +1,272 @@
 // DLT File Handler
 //
 // This file provides functionality for reading and parsing DLT files.
-// It uses memory mapping for efficient file access and builds an index
-// for fast message lookup.
+// It uses memory mapping for efficient file access, falling back to reading
+// files that can't be mapped, and builds an index for fast message lookup.
 
+use crate::parser::dlt_message::framing_header_len;
 use crate::parser::{DltMessage, Error, Result};
 use byteorder::ReadBytesExt;
+use lru::LruCache;
 use memmap2::{Mmap, MmapOptions};
 use rayon::prelude::*;
-use std::fs::File;
-use std::io::{Cursor, Read};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Cursor, Read};
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Default number of parsed messages kept in the cache
+pub const DEFAULT_CACHE_SIZE: usize = 4096;
+
+/// Version of the DLT protocol the parser understands
+const SUPPORTED_DLT_VERSION: u8 = 1;
+
+/// Maximum number of byte offsets kept or shown per kind of problem in diagnostics
+pub const MAX_DIAGNOSTIC_OFFSETS: usize = 8;
+
+/// Problems found while indexing a file, where bytes had to be skipped
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    /// Number of messages skipped per unsupported protocol version
+    pub unsupported_versions: BTreeMap<u8, usize>,
+    /// Number of regions of bytes in which no message could be framed
+    pub skipped_regions: usize,
+    /// Total number of bytes in the skipped regions
+    pub skipped_bytes: u64,
+    /// Byte offsets of the first skipped regions
+    pub skipped_offsets: Vec<u64>,
+}
+
+impl Diagnostics {
+    /// Record a region of bytes in which no message could be framed
+    fn add_skipped_region(&mut self, start: usize, end: usize) {
+        self.skipped_regions += 1;
+        self.skipped_bytes += (end - start) as u64;
+        if self.skipped_offsets.len() < MAX_DIAGNOSTIC_OFFSETS {
+            self.skipped_offsets.push(start as u64);
+        }
+    }
+
+    /// Check whether any bytes were skipped
+    pub fn is_empty(&self) -> bool {
+        self.unsupported_versions.is_empty() && self.skipped_regions == 0
+    }
+}
+
+/// Contents of a DLT file
+enum FileData {
+    /// Memory-mapped file
+    Mapped(Mmap),
+    /// File read into memory, where it can't be mapped
+    Buffered(Vec<u8>),
+}
+
+impl FileData {
+    /// Map a file, or read it into memory if that fails
+    ///
+    /// Pipes, character devices and files on some network file systems can't
+    /// be mapped, and empty files can't be mapped at all.
+    fn load(file: &mut File, path: &Path) -> io::Result<Self> {
+        let metadata = file.metadata()?;
+        if metadata.is_file() && metadata.len() == 0 {
+            return Ok(FileData::Buffered(Vec::new()));
+        }
+
+        if metadata.is_file() {
+            match unsafe { MmapOptions::new().map(&*file) } {
+                Ok(mmap) => {
+                    log::debug!("Mapped {} into memory", path.display());
+                    return Ok(FileData::Mapped(mmap));
+                }
+                Err(e) => log::warn!(
+                    "Failed to map {} ({}), reading it into memory instead",
+                    path.display(),
+                    e
+                ),
+            }
+        } else {
+            log::info!(
+                "{} is not a regular file, reading it into memory",
+                path.display()
+            );
+        }
+
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+        Ok(FileData::Buffered(data))
+    }
+
+    /// Get the file contents
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            FileData::Mapped(mmap) => mmap,
+            FileData::Buffered(data) => data,
+        }
+    }
+}
+
+/// Change of a file on disk since it was opened
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileChange {
+    /// The file is larger, e.g. because a logger appends to it
+    Grown,
+    /// The file is smaller; the mapped data past its new end can't be read
+    Truncated,
+    /// The file has the same size but a different modification time
+    Modified,
+}
 
 /// DLT file handler
 pub struct DltFile {
     /// Path to the DLT file
     path: PathBuf,
-    /// Memory-mapped file data
-    mmap: Arc<Mmap>,
+    /// File contents, memory-mapped where possible
+    data: FileData,
     /// Index of message positions in the file
     index: Vec<u64>,
     /// Total number of messages
     message_count: usize,
+    /// Problems found while indexing the file
+    diagnostics: Diagnostics,
+    /// Number of bytes covered by the index, less than the file size if
+    /// indexing stopped at a message limit
+    indexed_len: usize,
+    /// Whether indexing stopped at a message limit before the end of the file
+    limited: bool,
+    /// Modification time of the file when it was opened, if available
+    modified: Option<SystemTime>,
+    /// Cache of recently accessed messages (None if caching is disabled)
+    cache: Option<Mutex<LruCache<usize, DltMessage>>>,
 }
 
 impl DltFile {
     /// Open a DLT file and build its index
     pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with_cache_size(path, DEFAULT_CACHE_SIZE)
+    }
+
+    /// Open a DLT file, caching up to `cache_size` parsed messages (0 disables the cache)
+    pub fn open_with_cache_size(path: impl AsRef<Path>, cache_size: usize) -> Result<Self> {
+        Self::open_with_progress(path, cache_size, |_| {})
+    }
+
+    /// Open a DLT file, reporting the fraction (0.0 to 1.0) of the file scanned so far
+    pub fn open_with_progress(
+        path: impl AsRef<Path>,
+        cache_size: usize,
+        progress: impl Fn(f64),
+    ) -> Result<Self> {
+        Self::open_with_limit(path, cache_size, None, progress)
+    }
+
+    /// Open a DLT file, indexing at most `limit` messages from its start, and
+    /// report the fraction (0.0 to 1.0) of the work done so far
+    pub fn open_with_limit(
+        path: impl AsRef<Path>,
+        cache_size: usize,
+        limit: Option<usize>,
+        progress: impl Fn(f64),
+    ) -> Result<Self> {
         let path = path.as_ref().to_path_buf();
-        let file = File::open(&path)?;
+        let mut file = File::open(&path)?;
+        let modified = file.metadata().and_then(|m| m.modified()).ok();
 
-        // Memory map the file
-        let mmap = unsafe { MmapOptions::new().map(&file)? };
-        let mmap = Arc::new(mmap);
+        // Memory map the file, or read it if it can't be mapped
+        let data = FileData::load(&mut file, &path)?;
 
         // Build the index
-        let index = Self::build_index(&mmap)?;
+        let (index, diagnostics, indexed_len) =
+            Self::build_index(data.as_slice(), limit, &progress)?;
         let message_count = index.len();
+        let limited = indexed_len < data.as_slice().len();
+
+        let cache = NonZeroUsize::new(cache_size).map(|size| Mutex::new(LruCache::new(size)));
 
         Ok(Self {
             path,
-            mmap,
+            data,
             index,
             message_count,
+            diagnostics,
+            indexed_len,
+            limited,
+            modified,
+            cache,
         })
     }
 
     /// Build an index of message positions in the file
-    fn build_index(mmap: &Mmap) -> Result<Vec<u64>> {
+    ///
+    /// Messages of an unsupported protocol version are skipped by their length,
+    /// since their headers can't be parsed, and counted per version. Bytes in
+    /// which no framing header is found are skipped and recorded as well.
+    ///
+    /// Indexing stops after `limit` messages; the number of bytes covered by
+    /// the index is returned along with it.
+    fn build_index(
+        mmap: &[u8],
+        limit: Option<usize>,
+        progress: &dyn Fn(f64),
+    ) -> Result<(Vec<u64>, Diagnostics, usize)> {
         let mut index = Vec::new();
+        let mut diagnostics = Diagnostics::default();
         let mut pos = 0;
+        // Start of the bytes skipped since the last framing header
+        let mut skipped_from = None;
+        // Whether the bytes being skipped belong to an unsupported message,
+        // which is already counted
+        let mut in_unsupported = false;
+
+        // Report progress in steps of 1% of the file
+        let step = (mmap.len() / 100).max(1);
+        let mut next_report = step;
+
+        // Bytes covered by the index, up to where a limit stops indexing
+        let mut indexed_len = mmap.len();
 
         while pos < mmap.len() {
-            // Check if we have enough bytes for a storage header (16 bytes)
-            if pos + 16 > mmap.len() {
+            if limit.is_some_and(|limit| index.len() >= limit) {
+                indexed_len = pos;
                 break;
             }
 
-            // Check for DLT pattern
-            if mmap[pos] == b'D'
-                && mmap[pos + 1] == b'L'
-                && mmap[pos + 2] == b'T'
-                && mmap[pos + 3] == 0x01
-            {
-                index.push(pos as u64);
+            if pos >= next_report {
+                // With a limit, report whichever of the file and the limit is
+                // closer to being reached
+                let scanned = pos as f64 / mmap.len() as f64;
+                let counted = limit.map_or(0.0, |limit| index.len() as f64 / limit as f64);
+                progress(scanned.max(counted));
+                next_report = pos + step;
+            }
+
+            // Check if we have enough bytes for the smallest framing header (4 bytes)
+            if pos + 4 > mmap.len() {
+                if !in_unsupported {
+                    skipped_from.get_or_insert(pos);
+                }
+                break;
+            }
+
+            // Check for a storage header ("DLT" + 0x01) or serial header ("DLS" + 0x01)
+            if let Some(header_len) = framing_header_len(&mmap[pos..]) {
+                if let Some(start) = skipped_from.take() {
+                    diagnostics.add_skipped_region(start, pos);
+                }
+                in_unsupported = false;
 
                 // Read the standard header to get the message length
-                if pos + 20 <= mmap.len() {
-                    let mut cursor = Cursor::new(&mmap[pos + 16..pos + 20]);
-                    let _header_type = match cursor.read_u8() {
+                let std_pos = pos + header_len;
+                if std_pos + 4 <= mmap.len() {
+                    let mut cursor = Cursor::new(&mmap[std_pos..std_pos + 4]);
+                    let header_type = match cursor.read_u8() {
                         Ok(v) => v,
                         Err(_) => 0,
                     };
+
                     let _message_counter = match cursor.read_u8() {
                         Ok(v) => v,
                         Err(_) => 0,
@@ -80,13 +274,34 @@ impl DltFile {
                     let mut length_bytes = [0u8; 2];
                     let _ = cursor.read_exact(&mut length_bytes);
                     let length = u16::from_le_bytes(length_bytes) as usize;
+                    let length_fits = length >= header_len + 4 && pos + length <= mmap.len();
 
-                    // Skip to the next message
-                    if length > 0 && pos + length <= mmap.len() {
+                    // Skip messages of other protocol versions by their length,
+                    // or else resynchronize on the next framing header
+                    let version = (header_type >> 5) & 0x07;
+                    if version != SUPPORTED_DLT_VERSION {
+                        *diagnostics.unsupported_versions.entry(version).or_insert(0) += 1;
+                        if length_fits {
+                            pos += length;
+                        } else {
+                            in_unsupported = true;
+                            pos += header_len;
+                        }
+                        continue;
+                    }
+
+                    index.push(pos as u64);
+
+                    // Skip to the next message, if the length covers at least the headers
+                    if length_fits {
                         pos += length;
                         continue;
                     }
+                } else {
+                    index.push(pos as u64);
                 }
+            } else if !in_unsupported {
+                skipped_from.get_or_insert(pos);
             }
 
             // If we couldn't parse the message length or the pattern didn't match,
@@ -94,7 +309,11 @@ impl DltFile {
             pos += 1;
         }
 
-        Ok(index)
+        if let Some(start) = skipped_from {
+            diagnostics.add_skipped_region(start, mmap.len());
+        }
+
+        Ok((index, diagnostics, indexed_len))
     }
 
     /// Get the total number of messages in the file
@@ -102,13 +321,81 @@ impl DltFile {
         self.message_count
     }
 
+    /// Get the number of messages skipped per unsupported protocol version
+    pub fn unsupported_versions(&self) -> &BTreeMap<u8, usize> {
+        &self.diagnostics.unsupported_versions
+    }
+
+    /// Get the problems found while indexing the file
+    pub fn diagnostics(&self) -> &Diagnostics {
+        &self.diagnostics
+    }
+
+    /// Get the byte offset of a message in the file
+    pub fn message_offset(&self, idx: usize) -> Option<u64> {
+        self.index.get(idx).copied()
+    }
+
     /// Get the file path
     pub fn path(&self) -> &Path {
         &self.path
     }
 
+    /// Check whether the file is memory-mapped, rather than read into memory
+    pub fn is_mapped(&self) -> bool {
+        matches!(self.data, FileData::Mapped(_))
+    }
+
+    /// Check whether indexing stopped at a message limit before the end of the file
+    pub fn is_limited(&self) -> bool {
+        self.limited
+    }
+
+    /// Get the size of the file data in bytes
+    pub fn size(&self) -> u64 {
+        self.data.as_slice().len() as u64
+    }
+
+    /// Check whether the file on disk changed since it was opened
+    ///
+    /// Returns `None` if it didn't change or can't be checked (e.g. because it
+    /// was deleted, which leaves the opened data readable).
+    pub fn check_changed(&self) -> Option<FileChange> {
+        let metadata = fs::metadata(&self.path).ok()?;
+        match metadata.len().cmp(&self.size()) {
+            Ordering::Greater => Some(FileChange::Grown),
+            Ordering::Less => Some(FileChange::Truncated),
+            Ordering::Equal => {
+                (metadata.modified().ok() != self.modified).then_some(FileChange::Modified)
+            }
+        }
+    }
+
+    /// Get the part of the file data covered by the index
+    fn data(&self) -> &[u8] {
+        &self.data.as_slice()[..self.indexed_len]
+    }
+
     /// Get a message by its index
+    ///
+    /// Recently accessed messages are served from the cache. Use
+    /// `parse_message` when scanning the whole file to avoid evicting them.
     pub fn get_message(&self, idx: usize) -> Result<DltMessage> {
+        let Some(cache) = &self.cache else {
+            return self.parse_message(idx);
+        };
+
+        if let Some(message) = cache.lock().unwrap().get(&idx) {
+            return Ok(message.clone());
+        }
+
+        let message = self.parse_message(idx)?;
+        cache.lock().unwrap().put(idx, message.clone());
+        Ok(message)
+    }
+
+    /// Parse a message by its index, bypassing the cache
+    pub fn parse_message(&self, idx: usize) -> Result<DltMessage> {
         if idx >= self.message_count {
             return Err(Error::NotFound(format!(
                 "Message index out of bounds: {}",
@@ -116,22 +403,28 @@ impl DltFile {
             )));
         }
 
-        let pos = self.index[idx] as usize;
+        parse_at(self.data(), &self.index, idx)
+    }
 
-        // Find the end of this message (start of the next message or end of file)
-        let next_pos = if idx + 1 < self.message_count {
-            self.index[idx + 1] as usize
-        } else {
-            self.mmap.len()
-        };
+    /// Get the raw bytes of a message by its index, starting with its storage header
+    pub fn message_bytes(&self, idx: usize) -> Result<&[u8]> {
+        if idx >= self.message_count {
+            return Err(Error::NotFound(format!(
+                "Message index out of bounds: {}",
+                idx
+            )));
+        }
 
-        // Parse the message
-        let data = &self.mmap[pos..next_pos];
-        let message = DltMessage::parse(data).map_err(|e| {
-            Error::Format(format!("Failed to parse message at index {}: {}", idx, e))
-        })?;
+        Ok(message_slice(self.data(), &self.index, idx))
+    }
 
-        Ok(message)
+    /// Iterate over the messages in file order, bypassing the cache
+    pub fn iter(&self) -> Messages<'_> {
+        Messages {
+            data: self.data(),
+            index: &self.index,
+            idx: 0,
+        }
     }
 
     /// Get multiple messages in a range
@@ -140,7 +433,7 @@ impl DltFile {
 
         (start..end)
             .into_par_iter()
-            .map(|idx| self.get_message(idx))
+            .map(|idx| self.parse_message(idx))
             .collect()
     }
 
@@ -151,10 +444,113 @@ impl DltFile {
     {
         (0..self.message_count)
             .into_par_iter()
-            .filter_map(|idx| match self.get_message(idx) {
+            .filter_map(|idx| match self.parse_message(idx) {
                 Ok(msg) if predicate(&msg) => Some(idx),
                 _ => None,
             })
             .collect()
     }
 }
+
+/// Parse the message at a position in the index of `data`
+fn parse_at(data: &[u8], index: &[u64], idx: usize) -> Result<DltMessage> {
+    DltMessage::parse(message_slice(data, index, idx))
+        .map_err(|e| Error::Format(format!("Failed to parse message at index {}: {}", idx, e)))
+}
+
+/// Get the data of the message at a position in the index of `data`
+///
+/// A message extends to the start of the next message or the end of the data.
+fn message_slice<'a>(data: &'a [u8], index: &[u64], idx: usize) -> &'a [u8] {
+    let pos = index[idx] as usize;
+    let next_pos = index.get(idx + 1).map_or(data.len(), |&next| next as usize);
+    &data[pos..next_pos]
+}
+
+/// Iterator over the messages of a file, created by [`DltFile::iter`]
+pub struct Messages<'a> {
+    /// Mapped file data
+    data: &'a [u8],
+    /// Index of message positions in the data
+    index: &'a [u64],
+    /// Index of the next message
+    idx: usize,
+}
+
+impl Iterator for Messages<'_> {
+    type Item = Result<DltMessage>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.idx >= self.index.len() {
+            return None;
+        }
+
+        let message = parse_at(self.data, self.index, self.idx);
+        self.idx += 1;
+        Some(message)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.index.len() - self.idx;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Messages<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn indexes_serial_framed_messages() {
+        let file = testing::write_file(&[
+            testing::serial_message("APP1", "CTX1", 4, "first"),
+            testing::serial_message("APP2", "CTX2", 2, "second"),
+        ]);
+
+        let dlt = DltFile::open(file.path()).unwrap();
+        assert_eq!(dlt.message_count(), 2);
+
+        let msg = dlt.get_message(1).unwrap();
+        assert!(msg.storage_header.is_none());
+        assert_eq!(msg.app_id().as_deref(), Some("APP2"));
+        assert_eq!(msg.payload, b"second");
+        assert_eq!(msg.payload_text.as_deref(), Some("second"));
+    }
+
+    #[test]
+    fn opens_an_empty_file() {
+        let file = testing::write_file(&[]);
+
+        let dlt = DltFile::open(file.path()).unwrap();
+        assert_eq!(dlt.message_count(), 0);
+        assert_eq!(dlt.iter().count(), 0);
+        assert!(dlt.get_message(0).is_err());
+    }
+
+    #[test]
+    fn skips_unsupported_versions_by_their_length() {
+        // A version 2 message whose payload looks like a storage header
+        let mut unsupported = testing::message(1, "APP2", "CTX2", 4, "DLT\x01 inside");
+        unsupported[16] = 0x41;
+
+        let file = testing::write_file(&[
+            testing::message(0, "APP1", "CTX1", 4, "first"),
+            unsupported,
+            testing::message(2, "APP3", "CTX3", 4, "last"),
+        ]);
+
+        let dlt = DltFile::open(file.path()).unwrap();
+        assert_eq!(dlt.message_count(), 2);
+        assert_eq!(
+            dlt.get_message(1).unwrap().app_id().as_deref(),
+            Some("APP3")
+        );
+
+        let diagnostics = dlt.diagnostics();
+        assert_eq!(diagnostics.unsupported_versions.get(&2), Some(&1));
+        assert_eq!(diagnostics.skipped_regions, 0);
+    }
+}
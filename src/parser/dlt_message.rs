@@ -2,9 +2,23 @@
 //
 // This file defines the structures for DLT messages according to the DLT specification.
 
+use super::verbose::{count_arguments, decode_arguments, Argument};
 use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
 use chrono::{DateTime, TimeZone, Utc};
-use std::io::{Cursor, Read, Result as IoResult};
+use regex::Regex;
+use std::borrow::Cow;
+use std::io::{self, Cursor, Read, Result as IoResult};
+
+/// Maximum number of payload bytes included in the hex dump used for searching
+const MAX_SEARCHABLE_PAYLOAD_LEN: usize = 4096;
+
+/// Separator shown in place of line breaks in a payload
+pub const LINE_SEPARATOR: char = '⏎';
+
+/// Pattern at the start of a storage header ("DLT" + 0x01)
+pub const STORAGE_HEADER_PATTERN: [u8; 4] = *b"DLT\x01";
+/// Pattern at the start of a serial header ("DLS" + 0x01)
+pub const SERIAL_HEADER_PATTERN: [u8; 4] = *b"DLS\x01";
 
 /// DLT message log levels
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
@@ -33,6 +47,58 @@ impl From<u8> for LogLevel {
     }
 }
 
+impl LogLevel {
+    /// Parse a log level from its name (e.g. "warn" or "Warning") or its raw
+    /// value (e.g. "7" for a non-standard level)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "fatal" => Some(LogLevel::Fatal),
+            "error" => Some(LogLevel::Error),
+            "warn" | "warning" => Some(LogLevel::Warning),
+            "info" => Some(LogLevel::Info),
+            "debug" => Some(LogLevel::Debug),
+            "verbose" => Some(LogLevel::Verbose),
+            other => other.parse::<u8>().ok().map(LogLevel::from),
+        }
+    }
+
+    /// Get the name of the log level as accepted by `from_name`
+    ///
+    /// Non-standard levels are named by their raw value.
+    pub fn name(&self) -> Cow<'static, str> {
+        match self {
+            LogLevel::Fatal => Cow::Borrowed("fatal"),
+            LogLevel::Error => Cow::Borrowed("error"),
+            LogLevel::Warning => Cow::Borrowed("warn"),
+            LogLevel::Info => Cow::Borrowed("info"),
+            LogLevel::Debug => Cow::Borrowed("debug"),
+            LogLevel::Verbose => Cow::Borrowed("verbose"),
+            LogLevel::Unknown(value) => Cow::Owned(value.to_string()),
+        }
+    }
+
+    /// Get the rank of the log level, higher is more severe
+    ///
+    /// Ranges from 6 for `Fatal` down to 1 for `Verbose`; `Unknown` levels rank
+    /// below `Verbose` at 0.
+    pub fn severity(&self) -> u8 {
+        match self {
+            LogLevel::Fatal => 6,
+            LogLevel::Error => 5,
+            LogLevel::Warning => 4,
+            LogLevel::Info => 3,
+            LogLevel::Debug => 2,
+            LogLevel::Verbose => 1,
+            LogLevel::Unknown(_) => 0,
+        }
+    }
+
+    /// Check if the log level is as severe as another one, or more
+    pub fn is_at_least(&self, other: LogLevel) -> bool {
+        self.severity() >= other.severity()
+    }
+}
+
 /// DLT message types
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum MessageType {
@@ -56,6 +122,40 @@ impl From<u8> for MessageType {
     }
 }
 
+impl MessageType {
+    /// Parse a message type from its name (e.g. "control" or "log")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "log" => Some(MessageType::Log),
+            "trace" => Some(MessageType::TraceVariable),
+            "network" => Some(MessageType::NetworkTrace),
+            "control" => Some(MessageType::Control),
+            _ => None,
+        }
+    }
+
+    /// Get the name of the message type as accepted by `from_name`
+    pub fn name(&self) -> &'static str {
+        match self {
+            MessageType::Log => "log",
+            MessageType::TraceVariable => "trace",
+            MessageType::NetworkTrace => "network",
+            MessageType::Control => "control",
+            MessageType::Unknown(_) => "unknown",
+        }
+    }
+}
+
+/// Fields of a message that text patterns are matched against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchScope {
+    /// Only the payload text (or hex dump of binary payloads)
+    Payload,
+    /// The payload and the IDs
+    #[default]
+    All,
+}
+
 /// DLT Storage Header (16 bytes)
 #[derive(Debug, Clone)]
 pub struct DltStorageHeader {
@@ -104,9 +204,7 @@ impl DltStorageHeader {
     }
 
     pub fn ecu_id_str(&self) -> String {
-        String::from_utf8_lossy(&self.ecu_id)
-            .trim_end_matches('\0')
-            .to_string()
+        id_to_string(&self.ecu_id)
     }
 
     pub fn is_valid(&self) -> bool {
@@ -126,10 +224,10 @@ pub struct DltStandardHeader {
     pub message_counter: u8,
     /// Overall length of the message in bytes (including all headers)
     pub length: u16,
-    /// Message type (log, trace, etc.)
-    pub message_type: MessageType,
     /// Version number of the DLT protocol
     pub version: u8,
+    /// Payload is encoded most significant byte first
+    pub big_endian: bool,
 }
 
 impl DltStandardHeader {
@@ -140,15 +238,15 @@ impl DltStandardHeader {
 
         // Extract fields from header_type
         let use_extended_header = (header_type & 0x01) != 0;
+        let big_endian = (header_type & 0x02) != 0;
         let version = (header_type >> 5) & 0x07;
-        let message_type_value = (header_type >> 1) & 0x07;
 
         Ok(Self {
             use_extended_header,
             message_counter,
             length,
-            message_type: MessageType::from(message_type_value),
             version,
+            big_endian,
         })
     }
 }
@@ -164,8 +262,12 @@ pub struct DltExtendedHeader {
     pub app_id: [u8; 4],
     /// Context ID (up to 4 characters)
     pub context_id: [u8; 4],
-    /// Log level
+    /// Message type (MSTP)
+    pub message_type: MessageType,
+    /// Log level, from the message type info (MTIN) of log messages
     pub log_level: LogLevel,
+    /// Payload is encoded in verbose mode (VERB)
+    pub verbose: bool,
 }
 
 impl DltExtendedHeader {
@@ -179,36 +281,69 @@ impl DltExtendedHeader {
         let mut context_id = [0u8; 4];
         data.read_exact(&mut context_id)?;
 
-        // Extract log level from message_info
-        let log_level_value = (message_info >> 4) & 0x07;
+        // Split the message info into the verbose flag (VERB), the message type
+        // (MSTP) and the message type info (MTIN), which is the log level of
+        // log messages
+        let verbose = (message_info & 0x01) != 0;
+        let message_type = MessageType::from((message_info >> 1) & 0x07);
+        let message_type_info = (message_info >> 4) & 0x0f;
 
         Ok(Self {
             message_info,
             argument_count,
             app_id,
             context_id,
-            log_level: LogLevel::from(log_level_value),
+            message_type,
+            log_level: LogLevel::from(message_type_info),
+            verbose,
         })
     }
 
     pub fn app_id_str(&self) -> String {
-        String::from_utf8_lossy(&self.app_id)
-            .trim_end_matches('\0')
-            .to_string()
+        id_to_string(&self.app_id)
     }
 
     pub fn context_id_str(&self) -> String {
-        String::from_utf8_lossy(&self.context_id)
-            .trim_end_matches('\0')
-            .to_string()
+        id_to_string(&self.context_id)
+    }
+
+    /// Check whether the payload is encoded in verbose mode
+    pub fn is_verbose(&self) -> bool {
+        self.verbose
+    }
+}
+
+/// Convert a 4 byte ID to a string, keeping only printable ASCII characters
+///
+/// IDs are padded with NULs (or sometimes spaces) and corrupt files may
+/// contain arbitrary bytes, which would break the layout of the TUI.
+fn id_to_string(id: &[u8; 4]) -> String {
+    id.iter()
+        .filter(|b| b.is_ascii_graphic())
+        .map(|&b| b as char)
+        .collect()
+}
+
+/// Get the length of the framing header at the start of `data`
+///
+/// Messages in a DLT file are either prefixed with a storage header (16 bytes)
+/// or, for streams captured from a serial line, with a serial header (4 bytes).
+/// Returns `None` if neither pattern is found.
+pub fn framing_header_len(data: &[u8]) -> Option<usize> {
+    if data.len() >= 16 && data[..4] == STORAGE_HEADER_PATTERN {
+        Some(16)
+    } else if data.len() >= 4 && data[..4] == SERIAL_HEADER_PATTERN {
+        Some(4)
+    } else {
+        None
     }
 }
 
 /// Complete DLT Message
 #[derive(Debug, Clone)]
 pub struct DltMessage {
-    /// Storage header
-    pub storage_header: DltStorageHeader,
+    /// Storage header (absent for serial framed messages)
+    pub storage_header: Option<DltStorageHeader>,
     /// Standard header
     pub standard_header: DltStandardHeader,
     /// Extended header (optional)
@@ -223,7 +358,13 @@ impl DltMessage {
     pub fn parse(data: &[u8]) -> IoResult<Self> {
         let mut cursor = Cursor::new(data);
 
-        let storage_header = DltStorageHeader::parse(&mut cursor)?;
+        // Serial framed messages carry no storage header, only the pattern
+        let storage_header = if data.starts_with(&SERIAL_HEADER_PATTERN) {
+            cursor.set_position(SERIAL_HEADER_PATTERN.len() as u64);
+            None
+        } else {
+            Some(DltStorageHeader::parse(&mut cursor)?)
+        };
         let standard_header = DltStandardHeader::parse(&mut cursor)?;
 
         let extended_header = if standard_header.use_extended_header {
@@ -232,15 +373,37 @@ impl DltMessage {
             None
         };
 
-        // Calculate payload size and read payload
+        // Calculate payload size, rejecting lengths that don't fit the headers or the data
         let headers_size = cursor.position() as usize;
-        let payload_size = standard_header.length as usize - headers_size;
+        let length = standard_header.length as usize;
+        if length < headers_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "message length {} is shorter than its headers ({} bytes)",
+                    length, headers_size
+                ),
+            ));
+        }
+        if length > data.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "message length {} exceeds the available {} bytes",
+                    length,
+                    data.len()
+                ),
+            ));
+        }
+        let payload_size = length - headers_size;
+
+        // Read the payload
 
         let mut payload = vec![0u8; payload_size];
         cursor.read_exact(&mut payload)?;
 
         // Try to parse payload as text
-        let payload_text = Self::parse_payload_text(&payload, &extended_header);
+        let payload_text = Self::parse_payload_text(&payload, &standard_header, &extended_header);
 
         Ok(Self {
             storage_header,
@@ -251,27 +414,56 @@ impl DltMessage {
         })
     }
 
+    /// Get the payload as text: the decoded arguments of verbose messages joined
+    /// by spaces, or the payload itself if it is printable
     fn parse_payload_text(
         payload: &[u8],
-        _extended_header: &Option<DltExtendedHeader>,
+        standard_header: &DltStandardHeader,
+        extended_header: &Option<DltExtendedHeader>,
     ) -> Option<String> {
-        // Simple heuristic: if it looks like ASCII/UTF-8 text, return it as a string
-        if payload
-            .iter()
-            .all(|&b| b >= 32 && b < 127 || b == b'\n' || b == b'\r' || b == b'\t')
-        {
+        // Verbose messages are shown argument by argument, so strings mixed with
+        // numbers stay readable
+        let arguments = extended_header
+            .as_ref()
+            .filter(|h| h.is_verbose())
+            .and_then(|h| decode_arguments(payload, h.argument_count, standard_header.big_endian))
+            .filter(|arguments| !arguments.is_empty());
+        if let Some(arguments) = arguments {
+            let values: Vec<String> = arguments.iter().map(|arg| arg.value.to_string()).collect();
+            return Some(values.join(" "));
+        }
+
+        // Simple heuristic: if it looks like ASCII/UTF-8 text, return it as a string.
+        // Escape characters are allowed for text colored with ANSI escape sequences.
+        if payload.iter().all(|&b| {
+            (0x20..0x7f).contains(&b) || b == b'\n' || b == b'\r' || b == b'\t' || b == 0x1b
+        }) {
             String::from_utf8(payload.to_vec()).ok()
         } else {
             None
         }
     }
 
+    /// Get the length of the headers in bytes, from the storage header (or serial
+    /// pattern) up to the payload
+    pub fn header_len(&self) -> usize {
+        // The length is counted from the start of the message, like in `parse`
+        self.standard_header.length as usize - self.payload.len()
+    }
+
     pub fn timestamp(&self) -> DateTime<Utc> {
-        self.storage_header.timestamp()
+        match &self.storage_header {
+            Some(header) => header.timestamp(),
+            // Serial framed messages have no timestamp, use the epoch
+            None => Utc.timestamp_opt(0, 0).single().unwrap(),
+        }
     }
 
     pub fn ecu_id(&self) -> String {
-        self.storage_header.ecu_id_str()
+        self.storage_header
+            .as_ref()
+            .map(|h| h.ecu_id_str())
+            .unwrap_or_default()
     }
 
     pub fn app_id(&self) -> Option<String> {
@@ -282,23 +474,212 @@ impl DltMessage {
         self.extended_header.as_ref().map(|h| h.context_id_str())
     }
 
+    /// Get the log level, which only log messages have
     pub fn log_level(&self) -> Option<LogLevel> {
-        self.extended_header.as_ref().map(|h| h.log_level)
+        self.extended_header
+            .as_ref()
+            .filter(|h| h.message_type == MessageType::Log)
+            .map(|h| h.log_level)
     }
 
+    /// Get the message type from the extended header
+    ///
+    /// The standard header carries no message type, so messages without an
+    /// extended header are (non-verbose) log messages.
     pub fn message_type(&self) -> MessageType {
-        self.standard_header.message_type
+        self.extended_header
+            .as_ref()
+            .map_or(MessageType::Log, |h| h.message_type)
+    }
+
+    /// Check whether the payload is encoded in verbose mode
+    pub fn is_verbose(&self) -> bool {
+        self.extended_header.as_ref().is_some_and(|h| h.verbose)
+    }
+
+    /// Decode the verbose mode arguments of the payload
+    ///
+    /// Returns `None` for non-verbose messages or if decoding fails.
+    pub fn arguments(&self) -> Option<Vec<Argument>> {
+        let header = self.extended_header.as_ref().filter(|h| h.is_verbose())?;
+        decode_arguments(
+            &self.payload,
+            header.argument_count,
+            self.standard_header.big_endian,
+        )
+    }
+
+    /// Compare the number of arguments declared in the extended header (NOAR) with
+    /// the number found in the payload of a verbose message
+    ///
+    /// Returns the declared and the found number if they differ, or `None` if they
+    /// match or the payload can't be decoded.
+    pub fn argument_count_mismatch(&self) -> Option<(u8, usize)> {
+        let header = self.extended_header.as_ref().filter(|h| h.is_verbose())?;
+        let found = count_arguments(&self.payload, self.standard_header.big_endian)?;
+        (found != header.argument_count as usize).then_some((header.argument_count, found))
     }
 
     pub fn payload_as_text(&self) -> String {
         self.payload_text.clone().unwrap_or_else(|| {
             // Fallback to hex representation
-            payload_to_hex_string(&self.payload)
+            hex_dump(&self.payload)
         })
     }
+
+    /// Get the payload as text for display, covering at most `max_len` bytes of the
+    /// text (or of the payload, if it is binary)
+    ///
+    /// Longer payloads are cut with a "… (N more bytes)" marker; the full
+    /// payload stays available in `payload`, e.g. for export.
+    pub fn payload_display_text(&self, max_len: usize) -> Cow<'_, str> {
+        match &self.payload_text {
+            Some(text) => cut_display_text(text, max_len),
+            None if self.payload.len() <= max_len => Cow::Owned(hex_dump(&self.payload)),
+            None => Cow::Owned(format!(
+                "{}\n… ({} more bytes)",
+                hex_dump(&self.payload[..max_len]),
+                self.payload.len() - max_len
+            )),
+        }
+    }
+
+    /// Check whether a pattern matches the fields of the message in the given scope
+    ///
+    /// This is the single place where searching and filtering by text decide
+    /// whether a message matches.
+    pub fn matches_text(&self, pattern: &Regex, scope: MatchScope) -> bool {
+        if pattern.is_match(&self.searchable_text()) {
+            return true;
+        }
+
+        scope == MatchScope::All
+            && self
+                .app_id()
+                .into_iter()
+                .chain(self.context_id())
+                .chain(std::iter::once(self.ecu_id()))
+                .any(|id| pattern.is_match(&id))
+    }
+
+    /// Get the text that searches and filters match against
+    ///
+    /// This is the payload text, or the hex dump shown for binary payloads.
+    /// The hex dump only covers the start of large payloads to bound the
+    /// work spent per message.
+    pub fn searchable_text(&self) -> Cow<'_, str> {
+        match &self.payload_text {
+            Some(text) => Cow::Borrowed(text),
+            None => {
+                let len = self.payload.len().min(MAX_SEARCHABLE_PAYLOAD_LEN);
+                Cow::Owned(hex_dump(&self.payload[..len]))
+            }
+        }
+    }
+
+    /// Get the payload on one line, taking at most `limit` characters
+    ///
+    /// Line breaks in text payloads are shown as a separator, and payloads are
+    /// cut at `max_len` bytes like `payload_display_text`; binary payloads give
+    /// the first line of their hex dump, as searched. ANSI escape sequences are
+    /// removed if `strip_escapes` is set.
+    pub fn payload_line(
+        &self,
+        max_len: usize,
+        strip_escapes: bool,
+        limit: Option<usize>,
+    ) -> String {
+        let payload = if self.payload_text.is_some() {
+            self.payload_display_text(max_len)
+        } else {
+            self.searchable_text()
+        };
+        let text = if self.payload_text.is_some() {
+            &payload[..]
+        } else {
+            payload.lines().next().unwrap_or("")
+        };
+        if strip_escapes {
+            collapse_lines(&strip_ansi(text), limit)
+        } else {
+            collapse_lines(text, limit)
+        }
+    }
+}
+
+/// Cut text for display at `max_len` bytes, adding a "… (N more bytes)" marker
+pub fn cut_display_text(text: &str, max_len: usize) -> Cow<'_, str> {
+    if text.len() <= max_len {
+        return Cow::Borrowed(text);
+    }
+    let mut end = max_len;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    Cow::Owned(format!(
+        "{}… ({} more bytes)",
+        &text[..end],
+        text.len() - end
+    ))
+}
+
+/// Join the lines of a payload into a single line, separated by `LINE_SEPARATOR`
+///
+/// Takes at most `limit` characters, so only the visible part of long payloads is
+/// copied.
+pub fn collapse_lines(text: &str, limit: Option<usize>) -> String {
+    text.trim_end_matches(['\r', '\n'])
+        .chars()
+        .filter(|&c| c != '\r')
+        .map(|c| if c == '\n' { LINE_SEPARATOR } else { c })
+        .take(limit.unwrap_or(usize::MAX))
+        .collect()
+}
+
+/// Remove ANSI escape sequences (e.g. colors) from text
+///
+/// Control sequences (`ESC [ ... final`) and operating system commands
+/// (`ESC ] ... BEL` or `ESC ] ... ESC \`) are removed whole, other escapes are
+/// two characters long.
+pub fn strip_ansi(text: &str) -> Cow<'_, str> {
+    if !text.contains('\x1b') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' {
+                        chars.next_if_eq(&'\\');
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Cow::Owned(result)
 }
 
-fn payload_to_hex_string(payload: &[u8]) -> String {
+/// Format bytes as a hex dump with offsets and an ASCII column, 16 bytes per line
+pub fn hex_dump(payload: &[u8]) -> String {
     let mut result = String::new();
     for (i, chunk) in payload.chunks(16).enumerate() {
         if i > 0 {
@@ -329,7 +710,7 @@ fn payload_to_hex_string(payload: &[u8]) -> String {
         // ASCII representation
         result.push_str(" |");
         for &byte in chunk {
-            if byte >= 32 && byte < 127 {
+            if (0x20..0x7f).contains(&byte) {
                 result.push(byte as char);
             } else {
                 result.push('.');
@@ -340,3 +721,150 @@ fn payload_to_hex_string(payload: &[u8]) -> String {
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    /// Overwrite the length in the standard header of a message with a storage header
+    fn with_length(mut data: Vec<u8>, length: u16) -> Vec<u8> {
+        data[18..20].copy_from_slice(&length.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn parses_a_valid_message() {
+        let data = testing::message(1, "APP1", "CTX1", 4, "hello");
+        let msg = DltMessage::parse(&data).unwrap();
+        assert_eq!(msg.app_id().as_deref(), Some("APP1"));
+        assert_eq!(msg.payload, b"hello");
+    }
+
+    #[test]
+    fn rejects_a_length_shorter_than_the_headers() {
+        let data = testing::message(1, "APP1", "CTX1", 4, "hello");
+        for length in [0, 1, 20, 29] {
+            let err = DltMessage::parse(&with_length(data.clone(), length)).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+
+    #[test]
+    fn rejects_a_length_beyond_the_data() {
+        let data = testing::message(1, "APP1", "CTX1", 4, "hello");
+        let len = data.len() as u16;
+        for length in [len + 1, u16::MAX] {
+            let err = DltMessage::parse(&with_length(data.clone(), length)).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+
+        // The data may also be cut after the headers
+        let err = DltMessage::parse(&data[..data.len() - 2]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn rejects_truncated_headers() {
+        let data = testing::message(1, "APP1", "CTX1", 4, "hello");
+        // Every cut inside the storage, standard or extended header
+        for end in 0..30 {
+            assert!(DltMessage::parse(&data[..end]).is_err(), "cut at {}", end);
+        }
+
+        let data = testing::serial_message("APP1", "CTX1", 4, "hello");
+        for end in 0..18 {
+            assert!(DltMessage::parse(&data[..end]).is_err(), "cut at {}", end);
+        }
+    }
+
+    #[test]
+    fn ids_keep_only_printable_ascii() {
+        assert_eq!(id_to_string(b"APP1"), "APP1");
+        assert_eq!(id_to_string(b"AB\0\0"), "AB");
+        assert_eq!(id_to_string(b"AB\0C"), "ABC");
+        assert_eq!(id_to_string(&[b'A', 0xc3, 0xa4, b'B']), "AB");
+        assert_eq!(id_to_string(&[0xff, b'X', 0x1b, 0x80]), "X");
+        assert_eq!(id_to_string(b"\0\0\0\0"), "");
+        assert_eq!(id_to_string(b"    "), "");
+    }
+
+    #[test]
+    fn log_levels_are_ordered_by_severity() {
+        let levels = [
+            LogLevel::Fatal,
+            LogLevel::Error,
+            LogLevel::Warning,
+            LogLevel::Info,
+            LogLevel::Debug,
+            LogLevel::Verbose,
+            LogLevel::Unknown(0),
+        ];
+        for pair in levels.windows(2) {
+            assert!(pair[0].severity() > pair[1].severity(), "{:?}", pair);
+            assert!(pair[0].is_at_least(pair[1]));
+            assert!(!pair[1].is_at_least(pair[0]));
+        }
+        for level in levels {
+            assert!(level.is_at_least(level));
+        }
+    }
+
+    #[test]
+    fn unknown_log_levels_rank_below_verbose() {
+        for value in [0, 7, 15] {
+            let level = LogLevel::from(value);
+            assert_eq!(level, LogLevel::Unknown(value));
+            assert!(!level.is_at_least(LogLevel::Verbose));
+            assert!(level.is_at_least(LogLevel::Unknown(1)));
+        }
+    }
+
+    /// Parse a log message of APP1/CTX1 from ECU1
+    fn parse_message(payload: &str) -> DltMessage {
+        DltMessage::parse(&testing::message(1, "APP1", "CTX1", 4, payload)).unwrap()
+    }
+
+    #[test]
+    fn text_matches_the_payload_in_every_scope() {
+        let msg = parse_message("connection timeout");
+        let pattern = Regex::new("time.ut").unwrap();
+        assert!(msg.matches_text(&pattern, MatchScope::Payload));
+        assert!(msg.matches_text(&pattern, MatchScope::All));
+
+        let pattern = Regex::new("refused").unwrap();
+        assert!(!msg.matches_text(&pattern, MatchScope::Payload));
+        assert!(!msg.matches_text(&pattern, MatchScope::All));
+    }
+
+    #[test]
+    fn text_matches_the_ids_only_in_the_all_scope() {
+        let msg = parse_message("hello");
+        for id in ["APP1", "CTX1", "ECU1"] {
+            let pattern = Regex::new(id).unwrap();
+            assert!(!msg.matches_text(&pattern, MatchScope::Payload), "{}", id);
+            assert!(msg.matches_text(&pattern, MatchScope::All), "{}", id);
+        }
+    }
+
+    #[test]
+    fn binary_payloads_match_their_hex_dump_and_ids() {
+        let mut msg = parse_message("");
+        msg.payload = vec![0xde, 0xad, 0xbe, 0xef];
+        msg.payload_text = None;
+
+        let pattern = Regex::new("de ad be ef").unwrap();
+        assert!(msg.matches_text(&pattern, MatchScope::Payload));
+
+        let pattern = Regex::new("APP1").unwrap();
+        assert!(!msg.matches_text(&pattern, MatchScope::Payload));
+        assert!(msg.matches_text(&pattern, MatchScope::All));
+    }
+
+    #[test]
+    fn payload_lines_keep_text_after_a_blank_first_line() {
+        let msg = parse_message("\r\nfirst\nsecond\n");
+        assert_eq!(msg.payload_line(usize::MAX, false, None), "⏎first⏎second");
+        assert_eq!(msg.payload_line(usize::MAX, false, Some(6)), "⏎first");
+    }
+}
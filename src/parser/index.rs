@@ -3,10 +3,13 @@
 // This file provides functionality for indexing DLT files to enable
 // fast message lookup and filtering.
 
-use std::collections::HashMap;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
-use crate::parser::{DltFile, LogLevel, Result};
+use crate::parser::{DltFile, DltMessage, LogLevel, Result};
 
 /// Index for DLT messages
 pub struct Index {
@@ -20,54 +23,73 @@ pub struct Index {
     log_level_index: HashMap<LogLevel, Vec<usize>>,
     /// Map of ECU IDs to message indices
     ecu_id_index: HashMap<String, Vec<usize>>,
+    /// Map of message indices to the number of counter values skipped before them
+    counter_gaps: BTreeMap<usize, u8>,
+    /// Indices of the messages that failed to parse
+    unparsable_messages: Vec<usize>,
 }
 
 impl Index {
     /// Create a new index for a DLT file
     pub fn new(file: Arc<DltFile>) -> Result<Self> {
+        Self::with_progress(file, |_| {})
+    }
+
+    /// Create a new index, reporting the fraction (0.0 to 1.0) of messages indexed so far
+    pub fn with_progress(file: Arc<DltFile>, progress: impl Fn(f64) + Sync) -> Result<Self> {
         let mut index = Self {
             file: file.clone(),
             app_id_index: HashMap::new(),
             context_id_index: HashMap::new(),
             log_level_index: HashMap::new(),
             ecu_id_index: HashMap::new(),
+            counter_gaps: BTreeMap::new(),
+            unparsable_messages: Vec::new(),
         };
 
         // Build the indices
-        index.build()?;
+        index.build(&progress)?;
 
         Ok(index)
     }
 
     /// Build all indices
-    fn build(&mut self) -> Result<()> {
-        let message_count = self.file.message_count();
-
-        for idx in 0..message_count {
-            let message = self.file.get_message(idx)?;
-
-            // Index by ECU ID
-            let ecu_id = message.ecu_id();
-            self.ecu_id_index.entry(ecu_id).or_default().push(idx);
+    ///
+    /// Messages are parsed in parallel into partial indices over contiguous
+    /// ranges, which are merged in order so each index list stays sorted.
+    /// Messages that fail to parse are left out of the indices and recorded.
+    fn build(&mut self, progress: &(dyn Fn(f64) + Sync)) -> Result<()> {
+        let file = &self.file;
+        let count = file.message_count();
 
-            // Index by application ID (if available)
-            if let Some(app_id) = message.app_id() {
-                self.app_id_index.entry(app_id).or_default().push(idx);
-            }
+        // Report progress in steps of 1% of the messages
+        let step = (count / 100).max(1);
+        let done = AtomicUsize::new(0);
 
-            // Index by context ID (if available)
-            if let Some(context_id) = message.context_id() {
-                self.context_id_index
-                    .entry(context_id)
-                    .or_default()
-                    .push(idx);
-            }
+        let partial = (0..count)
+            .into_par_iter()
+            .try_fold(PartialIndex::default, |mut partial, idx| -> Result<_> {
+                match file.parse_message(idx) {
+                    Ok(message) => partial.add(idx, &message),
+                    Err(_) => partial.add_unparsable(idx),
+                }
+                let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+                if done.is_multiple_of(step) {
+                    progress(done as f64 / count as f64);
+                }
+                Ok(partial)
+            })
+            .try_reduce(PartialIndex::default, |mut left, right| {
+                left.merge(right);
+                Ok(left)
+            })?;
 
-            // Index by log level (if available)
-            if let Some(log_level) = message.log_level() {
-                self.log_level_index.entry(log_level).or_default().push(idx);
-            }
-        }
+        self.app_id_index = partial.app_id_index;
+        self.context_id_index = partial.context_id_index;
+        self.log_level_index = partial.log_level_index;
+        self.ecu_id_index = partial.ecu_id_index;
+        self.counter_gaps = find_counter_gaps(&self.ecu_id_index, &partial.message_counters);
+        self.unparsable_messages = partial.unparsable_messages;
 
         Ok(())
     }
@@ -113,8 +135,199 @@ impl Index {
         self.ecu_id_index.get(ecu_id).cloned().unwrap_or_default()
     }
 
+    /// Get the number of messages missing before a message, if its counter skipped values
+    pub fn counter_gap(&self, idx: usize) -> Option<u8> {
+        self.counter_gaps.get(&idx).copied()
+    }
+
+    /// Get the number of messages whose counter skipped values
+    pub fn counter_gap_count(&self) -> usize {
+        self.counter_gaps.len()
+    }
+
+    /// Get the total number of messages missing according to the counters
+    pub fn dropped_message_count(&self) -> usize {
+        self.counter_gaps.values().map(|&n| n as usize).sum()
+    }
+
+    /// Get the indices of the messages that failed to parse, in ascending order
+    pub fn unparsable_messages(&self) -> &[usize] {
+        &self.unparsable_messages
+    }
+
     /// Get the DLT file
     pub fn file(&self) -> &DltFile {
         &self.file
     }
 }
+
+/// Indices over a contiguous range of messages, built by one worker
+#[derive(Default)]
+struct PartialIndex {
+    app_id_index: HashMap<String, Vec<usize>>,
+    context_id_index: HashMap<String, Vec<usize>>,
+    log_level_index: HashMap<LogLevel, Vec<usize>>,
+    ecu_id_index: HashMap<String, Vec<usize>>,
+    message_counters: Vec<u8>,
+    unparsable_messages: Vec<usize>,
+}
+
+impl PartialIndex {
+    /// Add a message to the indices
+    fn add(&mut self, idx: usize, message: &DltMessage) {
+        self.message_counters
+            .push(message.standard_header.message_counter);
+
+        // Index by ECU ID
+        self.ecu_id_index
+            .entry(message.ecu_id())
+            .or_default()
+            .push(idx);
+
+        // Index by application ID (if available)
+        if let Some(app_id) = message.app_id() {
+            self.app_id_index.entry(app_id).or_default().push(idx);
+        }
+
+        // Index by context ID (if available)
+        if let Some(context_id) = message.context_id() {
+            self.context_id_index
+                .entry(context_id)
+                .or_default()
+                .push(idx);
+        }
+
+        // Index by log level (if available)
+        if let Some(log_level) = message.log_level() {
+            self.log_level_index.entry(log_level).or_default().push(idx);
+        }
+    }
+
+    /// Record a message that failed to parse
+    fn add_unparsable(&mut self, idx: usize) {
+        // Keep the counters aligned with the message indices; the counter of
+        // this message is never compared, as it isn't in the ECU index
+        self.message_counters.push(0);
+        self.unparsable_messages.push(idx);
+    }
+
+    /// Append the indices of the range following this one
+    fn merge(&mut self, other: PartialIndex) {
+        merge_map(&mut self.app_id_index, other.app_id_index);
+        merge_map(&mut self.context_id_index, other.context_id_index);
+        merge_map(&mut self.log_level_index, other.log_level_index);
+        merge_map(&mut self.ecu_id_index, other.ecu_id_index);
+        self.message_counters.extend(other.message_counters);
+        self.unparsable_messages.extend(other.unparsable_messages);
+    }
+}
+
+/// Append the index lists of `other` to those of `map`
+fn merge_map<K: Eq + Hash>(map: &mut HashMap<K, Vec<usize>>, other: HashMap<K, Vec<usize>>) {
+    for (key, indices) in other {
+        map.entry(key).or_default().extend(indices);
+    }
+}
+
+/// Find the messages whose counter didn't follow the previous message of the same ECU
+///
+/// The counter wraps from 255 to 0, so the number of missing messages is only
+/// known modulo 256.
+fn find_counter_gaps(
+    ecu_id_index: &HashMap<String, Vec<usize>>,
+    counters: &[u8],
+) -> BTreeMap<usize, u8> {
+    let mut gaps = BTreeMap::new();
+    for indices in ecu_id_index.values() {
+        for pair in indices.windows(2) {
+            let missing = counters[pair[1]]
+                .wrapping_sub(counters[pair[0]])
+                .wrapping_sub(1);
+            if missing != 0 {
+                gaps.insert(pair[1], missing);
+            }
+        }
+    }
+    gaps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    /// Write a file of messages cycling through a few IDs and levels
+    fn write_messages(count: usize) -> tempfile::NamedTempFile {
+        let messages: Vec<Vec<u8>> = (0..count)
+            .map(|i| {
+                testing::message(
+                    i as u32,
+                    ["APP1", "APP2", "SYS"][i % 3],
+                    ["CTX1", "CTX2"][i % 2],
+                    (i % 6 + 1) as u8,
+                    &format!("message {}", i),
+                )
+            })
+            .collect();
+        testing::write_file(&messages)
+    }
+
+    /// Collect the messages of each application ID in a sequential pass
+    fn sequential_app_id_index(file: &DltFile) -> HashMap<String, Vec<usize>> {
+        let mut index: HashMap<String, Vec<usize>> = HashMap::new();
+        for (idx, message) in file.iter().enumerate() {
+            let app_id = message.unwrap().app_id().unwrap();
+            index.entry(app_id).or_default().push(idx);
+        }
+        index
+    }
+
+    #[test]
+    fn parallel_build_keeps_the_sequential_order() {
+        let tmp = write_messages(20_000);
+        let file = Arc::new(DltFile::open(tmp.path()).unwrap());
+        let index = Index::new(file.clone()).unwrap();
+
+        let expected = sequential_app_id_index(&file);
+        assert_eq!(index.app_ids().len(), expected.len());
+        for (app_id, indices) in &expected {
+            assert_eq!(&index.messages_by_app_id(app_id), indices, "{}", app_id);
+        }
+
+        let errors = index.messages_by_log_level(LogLevel::Error);
+        assert!(errors.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(errors.len(), 20_000 / 6 + 1);
+    }
+
+    /// Compare the time of the parallel build with that of a sequential pass
+    /// over a large file: `cargo test --release -- --ignored build_timing --nocapture`
+    #[test]
+    #[ignore]
+    fn build_timing() {
+        let tmp = write_messages(1_000_000);
+        let file = Arc::new(DltFile::open(tmp.path()).unwrap());
+
+        let start = std::time::Instant::now();
+        let mut sequential = PartialIndex::default();
+        for idx in 0..file.message_count() {
+            match file.parse_message(idx) {
+                Ok(message) => sequential.add(idx, &message),
+                Err(_) => sequential.add_unparsable(idx),
+            }
+        }
+        let sequential_time = start.elapsed();
+
+        let start = std::time::Instant::now();
+        let index = Index::new(file.clone()).unwrap();
+        let parallel_time = start.elapsed();
+
+        println!(
+            "sequential: {:?}, parallel: {:?} ({} threads)",
+            sequential_time,
+            parallel_time,
+            rayon::current_num_threads()
+        );
+        assert_eq!(index.app_id_index, sequential.app_id_index);
+        assert_eq!(index.log_level_index, sequential.log_level_index);
+    }
+}
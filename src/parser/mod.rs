@@ -3,13 +3,22 @@
 // This module is responsible for parsing DLT (Diagnostic Log and Trace) files.
 // It provides functionality to read, parse, and access DLT messages.
 
+mod decoder;
 mod dlt_file;
 mod dlt_message;
 mod index;
+mod verbose;
 
-pub use dlt_file::DltFile;
-pub use dlt_message::{DltMessage, LogLevel, MessageType};
+pub use decoder::{JsonDecoder, PayloadDecoder, PayloadDecoders, Utf8Decoder};
+pub use dlt_file::{
+    Diagnostics, DltFile, FileChange, Messages, DEFAULT_CACHE_SIZE, MAX_DIAGNOSTIC_OFFSETS,
+};
+pub use dlt_message::{
+    collapse_lines, cut_display_text, hex_dump, strip_ansi, DltMessage, LogLevel, MatchScope,
+    MessageType, LINE_SEPARATOR,
+};
 pub use index::Index;
+pub use verbose::{Argument, ArgumentValue};
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -0,0 +1,266 @@
+// Verbose Payload Decoder
+//
+// This file decodes the arguments of verbose mode DLT messages. Each argument
+// starts with a 32 bit type info word describing its type, followed by the
+// (optional) name and the value.
+
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use std::fmt;
+
+/// Type length mask (TYLE)
+const TYPE_LENGTH_MASK: u32 = 0x0000_000f;
+/// Boolean type (BOOL)
+const TYPE_BOOL: u32 = 0x0000_0010;
+/// Signed integer type (SINT)
+const TYPE_SINT: u32 = 0x0000_0020;
+/// Unsigned integer type (UINT)
+const TYPE_UINT: u32 = 0x0000_0040;
+/// Float type (FLOA)
+const TYPE_FLOA: u32 = 0x0000_0080;
+/// Array type (ARAY)
+const TYPE_ARAY: u32 = 0x0000_0100;
+/// String type (STRG)
+const TYPE_STRG: u32 = 0x0000_0200;
+/// Raw data type (RAWD)
+const TYPE_RAWD: u32 = 0x0000_0400;
+/// Variable info flag (VARI), the argument carries a name (and unit)
+const TYPE_VARI: u32 = 0x0000_0800;
+/// Fixed point flag (FIXP)
+const TYPE_FIXP: u32 = 0x0000_1000;
+/// Trace info type (TRAI)
+const TYPE_TRAI: u32 = 0x0000_2000;
+/// Struct type (STRU)
+const TYPE_STRU: u32 = 0x0000_4000;
+
+/// Value of a decoded argument
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgumentValue {
+    Bool(bool),
+    Signed(i64),
+    Unsigned(u64),
+    Float(f64),
+    String(String),
+    Raw(Vec<u8>),
+}
+
+impl fmt::Display for ArgumentValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgumentValue::Bool(value) => write!(f, "{}", value),
+            ArgumentValue::Signed(value) => write!(f, "{}", value),
+            ArgumentValue::Unsigned(value) => write!(f, "{}", value),
+            ArgumentValue::Float(value) => write!(f, "{}", value),
+            ArgumentValue::String(value) => write!(f, "{}", value),
+            ArgumentValue::Raw(bytes) => {
+                let hex: Vec<String> = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+                write!(f, "{}", hex.join(" "))
+            }
+        }
+    }
+}
+
+/// A decoded verbose mode argument
+#[derive(Debug, Clone, PartialEq)]
+pub struct Argument {
+    /// Raw type info word
+    pub type_info: u32,
+    /// Variable name, if the VARI flag is set
+    pub name: Option<String>,
+    /// Decoded value
+    pub value: ArgumentValue,
+}
+
+impl Argument {
+    /// Get the declared type name, e.g. "uint32" or "string"
+    pub fn type_name(&self) -> String {
+        let bits = bit_width(self.type_info).unwrap_or(0);
+        if self.type_info & TYPE_BOOL != 0 {
+            "bool".to_string()
+        } else if self.type_info & TYPE_SINT != 0 {
+            format!("sint{}", bits)
+        } else if self.type_info & TYPE_UINT != 0 {
+            format!("uint{}", bits)
+        } else if self.type_info & TYPE_FLOA != 0 {
+            format!("float{}", bits)
+        } else if self.type_info & TYPE_STRG != 0 {
+            "string".to_string()
+        } else if self.type_info & TYPE_RAWD != 0 {
+            "raw".to_string()
+        } else {
+            "unknown".to_string()
+        }
+    }
+}
+
+/// Get the width in bits encoded in the type length field
+fn bit_width(type_info: u32) -> Option<usize> {
+    match type_info & TYPE_LENGTH_MASK {
+        1 => Some(8),
+        2 => Some(16),
+        3 => Some(32),
+        4 => Some(64),
+        5 => Some(128),
+        _ => None,
+    }
+}
+
+/// Cursor over the payload reading values in the message's byte order
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let end = self.pos.checked_add(len)?;
+        let bytes = self.data.get(self.pos..end)?;
+        self.pos = end;
+        Some(bytes)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let bytes = self.bytes(2)?;
+        Some(if self.big_endian {
+            BigEndian::read_u16(bytes)
+        } else {
+            LittleEndian::read_u16(bytes)
+        })
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let bytes = self.bytes(4)?;
+        Some(if self.big_endian {
+            BigEndian::read_u32(bytes)
+        } else {
+            LittleEndian::read_u32(bytes)
+        })
+    }
+
+    /// Read an unsigned integer of `bits` width, widened to 64 bits
+    fn uint(&mut self, bits: usize) -> Option<u64> {
+        let bytes = self.bytes(bits / 8)?;
+        Some(if self.big_endian {
+            BigEndian::read_uint(bytes, bytes.len())
+        } else {
+            LittleEndian::read_uint(bytes, bytes.len())
+        })
+    }
+
+    /// Read a length-prefixed, NUL-terminated string
+    fn string(&mut self, len: usize) -> Option<String> {
+        let bytes = self.bytes(len)?;
+        let bytes = bytes.strip_suffix(&[0]).unwrap_or(bytes);
+        Some(String::from_utf8_lossy(bytes).into_owned())
+    }
+}
+
+/// Decode the arguments of a verbose mode payload
+///
+/// Returns `None` if the payload can't be decoded completely, or if it uses
+/// a type that isn't supported (arrays, structs, fixed point, 128 bit values).
+pub fn decode_arguments(payload: &[u8], count: u8, big_endian: bool) -> Option<Vec<Argument>> {
+    let mut reader = Reader {
+        data: payload,
+        pos: 0,
+        big_endian,
+    };
+
+    let mut arguments = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        arguments.push(decode_argument(&mut reader)?);
+    }
+
+    Some(arguments)
+}
+
+/// Count the arguments in a verbose mode payload, decoding them up to its end
+/// regardless of the number declared in the extended header
+///
+/// Returns `None` if an argument can't be decoded, as for [`decode_arguments`].
+pub fn count_arguments(payload: &[u8], big_endian: bool) -> Option<usize> {
+    let mut reader = Reader {
+        data: payload,
+        pos: 0,
+        big_endian,
+    };
+
+    let mut count = 0;
+    while reader.pos < payload.len() {
+        decode_argument(&mut reader)?;
+        count += 1;
+    }
+
+    Some(count)
+}
+
+/// Decode a single argument at the reader's position
+fn decode_argument(reader: &mut Reader) -> Option<Argument> {
+    let type_info = reader.u32()?;
+    if type_info & (TYPE_ARAY | TYPE_FIXP | TYPE_TRAI | TYPE_STRU) != 0 {
+        return None;
+    }
+    let has_name = type_info & TYPE_VARI != 0;
+
+    // Strings and raw data carry their length before the name
+    if type_info & (TYPE_STRG | TYPE_RAWD) != 0 {
+        let len = reader.u16()? as usize;
+        let name = if has_name {
+            let name_len = reader.u16()? as usize;
+            Some(reader.string(name_len)?)
+        } else {
+            None
+        };
+        let value = if type_info & TYPE_STRG != 0 {
+            ArgumentValue::String(reader.string(len)?)
+        } else {
+            ArgumentValue::Raw(reader.bytes(len)?.to_vec())
+        };
+        return Some(Argument {
+            type_info,
+            name,
+            value,
+        });
+    }
+
+    // Numeric values carry a name and a unit (the latter isn't shown)
+    let bits = bit_width(type_info).filter(|&bits| bits <= 64)?;
+    let name = if has_name {
+        let name_len = reader.u16()? as usize;
+        let unit_len = if type_info & TYPE_BOOL != 0 {
+            0
+        } else {
+            reader.u16()? as usize
+        };
+        let name = reader.string(name_len)?;
+        reader.bytes(unit_len)?;
+        Some(name)
+    } else {
+        None
+    };
+
+    let raw = reader.uint(bits)?;
+    let value = if type_info & TYPE_BOOL != 0 {
+        ArgumentValue::Bool(raw != 0)
+    } else if type_info & TYPE_SINT != 0 {
+        // Sign extend from the declared width
+        let shift = 64 - bits;
+        ArgumentValue::Signed(((raw << shift) as i64) >> shift)
+    } else if type_info & TYPE_UINT != 0 {
+        ArgumentValue::Unsigned(raw)
+    } else if type_info & TYPE_FLOA != 0 {
+        match bits {
+            32 => ArgumentValue::Float(f32::from_bits(raw as u32) as f64),
+            64 => ArgumentValue::Float(f64::from_bits(raw)),
+            _ => return None,
+        }
+    } else {
+        return None;
+    };
+
+    Some(Argument {
+        type_info,
+        name,
+        value,
+    })
+}
@@ -6,9 +6,10 @@ use rayon::prelude::*;
 use regex::Regex;
 use std::sync::Arc;
 
-use crate::parser::{DltFile, DltMessage};
+use crate::parser::{DltFile, DltMessage, MatchScope};
 
 /// Search engine for DLT messages
+#[derive(Clone)]
 pub struct SearchEngine {
     /// Search pattern
     pattern: Regex,
@@ -39,19 +40,21 @@ impl SearchEngine {
         })
     }
 
-    /// Search for the pattern in a DLT file
+    /// Search for the pattern in a DLT file, returning the matching message
+    /// indices in ascending order
     pub fn search(&self, file: &DltFile) -> Vec<usize> {
         // Apply the search in parallel
         (0..file.message_count())
             .into_par_iter()
-            .filter_map(|idx| match file.get_message(idx) {
+            .filter_map(|idx| match file.parse_message(idx) {
                 Ok(msg) if self.matches(&msg) => Some(idx),
                 _ => None,
             })
             .collect()
     }
 
-    /// Search for the pattern in a list of messages
+    /// Search for the pattern in a list of messages, returning the matching
+    /// positions in ascending order
     pub fn search_in_messages(&self, messages: &[DltMessage]) -> Vec<usize> {
         // Apply the search in parallel
         (0..messages.len())
@@ -66,46 +69,24 @@ impl SearchEngine {
             .collect()
     }
 
-    /// Search for the pattern in a list of message indices
+    /// Search for the pattern in a list of message indices, returning the
+    /// matching indices in ascending order (whatever the order of `indices`)
     pub fn search_in_indices(&self, file: &DltFile, indices: &[usize]) -> Vec<usize> {
         // Apply the search in parallel
-        indices
+        let mut results: Vec<usize> = indices
             .par_iter()
-            .filter_map(|&idx| match file.get_message(idx) {
+            .filter_map(|&idx| match file.parse_message(idx) {
                 Ok(msg) if self.matches(&msg) => Some(idx),
                 _ => None,
             })
-            .collect()
+            .collect();
+        results.sort_unstable();
+        results
     }
 
     /// Check if a message matches the search pattern
     pub fn matches(&self, message: &DltMessage) -> bool {
-        // Check if the payload text matches the pattern
-        if let Some(text) = &message.payload_text {
-            return self.pattern.is_match(text);
-        }
-
-        // Check if the application ID matches the pattern
-        if let Some(app_id) = message.app_id() {
-            if self.pattern.is_match(&app_id) {
-                return true;
-            }
-        }
-
-        // Check if the context ID matches the pattern
-        if let Some(ctx_id) = message.context_id() {
-            if self.pattern.is_match(&ctx_id) {
-                return true;
-            }
-        }
-
-        // Check if the ECU ID matches the pattern
-        let ecu_id = message.ecu_id();
-        if self.pattern.is_match(&ecu_id) {
-            return true;
-        }
-
-        false
+        message.matches_text(&self.pattern, MatchScope::All)
     }
 
     /// Get the search pattern
@@ -146,20 +127,56 @@ impl SearchEngine {
             return Ok(());
         }
 
-        // Get the current pattern as a string
+        // Recover the pattern as given, without the (?i) prefix added for
+        // case-insensitive searches; it is added back if needed
         let pattern_str = self.pattern.as_str();
-
-        // If switching from case-insensitive to case-sensitive, remove the (?i) prefix
-        let pattern = if !case_sensitive && pattern_str.starts_with("(?i)") {
-            pattern_str.to_string()
-        } else if case_sensitive && !pattern_str.starts_with("(?i)") {
-            // If switching from case-sensitive to case-insensitive, add the (?i) prefix
-            format!("(?i){}", pattern_str)
+        let pattern = if self.case_sensitive {
+            pattern_str
         } else {
-            // No change needed
-            pattern_str.to_string()
-        };
+            pattern_str.strip_prefix("(?i)").unwrap_or(pattern_str)
+        }
+        .to_string();
 
         self.set_pattern_with_case_sensitivity(pattern, case_sensitive)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn results_are_in_ascending_order() {
+        let messages: Vec<Vec<u8>> = (0..20_000)
+            .map(|i| {
+                let payload = if i % 7 == 0 { "timeout" } else { "ok" };
+                testing::message(i, "APP1", "CTX1", 4, payload)
+            })
+            .collect();
+        let tmp = testing::write_file(&messages);
+        let file = DltFile::open(tmp.path()).unwrap();
+        let engine = SearchEngine::new("timeout").unwrap();
+
+        let expected: Vec<usize> = (0..20_000).step_by(7).collect();
+        assert_eq!(engine.search(&file), expected);
+
+        // Unordered indices give the same results
+        let mut indices: Vec<usize> = (0..20_000).collect();
+        indices.reverse();
+        assert_eq!(engine.search_in_indices(&file, &indices), expected);
+    }
+
+    #[test]
+    fn switching_case_sensitivity_keeps_the_pattern() {
+        let mut engine = SearchEngine::with_case_sensitivity("error", false).unwrap();
+        assert_eq!(engine.pattern().as_str(), "(?i)error");
+
+        engine.set_case_sensitive(true).unwrap();
+        assert_eq!(engine.pattern().as_str(), "error");
+
+        engine.set_case_sensitive(false).unwrap();
+        engine.set_case_sensitive(false).unwrap();
+        assert_eq!(engine.pattern().as_str(), "(?i)error");
+    }
+}
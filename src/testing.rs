@@ -0,0 +1,59 @@
+// Test Messages
+//
+// This file builds DLT messages and files for the tests of the library and the
+// viewer, which both include it as a module.
+
+use std::io::Write;
+
+use tempfile::NamedTempFile;
+
+/// Build a non-verbose log message framed with a storage header, at a time in
+/// seconds since the epoch
+pub fn message(seconds: u32, app_id: &str, context_id: &str, level: u8, payload: &str) -> Vec<u8> {
+    let mut storage = b"DLT\x01".to_vec();
+    storage.extend(seconds.to_be_bytes());
+    storage.extend(0u32.to_be_bytes());
+    storage.extend(b"ECU1");
+    frame(storage, app_id, context_id, level, payload.as_bytes())
+}
+
+/// Build a non-verbose log message framed with a serial header
+pub fn serial_message(app_id: &str, context_id: &str, level: u8, payload: &str) -> Vec<u8> {
+    frame(
+        b"DLS\x01".to_vec(),
+        app_id,
+        context_id,
+        level,
+        payload.as_bytes(),
+    )
+}
+
+/// Append the standard and extended headers and the payload to a framing header
+fn frame(mut data: Vec<u8>, app_id: &str, context_id: &str, level: u8, payload: &[u8]) -> Vec<u8> {
+    // The length counts from the start of the framing header, like the parser
+    let length = data.len() + 4 + 10 + payload.len();
+    data.extend([0x21, 0]);
+    data.extend((length as u16).to_le_bytes());
+    data.extend([level << 4, 0]);
+    data.extend(id(app_id));
+    data.extend(id(context_id));
+    data.extend(payload);
+    data
+}
+
+/// Pad an ID with NULs to 4 bytes
+fn id(value: &str) -> [u8; 4] {
+    let mut id = [0u8; 4];
+    id[..value.len()].copy_from_slice(value.as_bytes());
+    id
+}
+
+/// Write messages to a temporary file, removed when it is dropped
+pub fn write_file(messages: &[Vec<u8>]) -> NamedTempFile {
+    let mut file = NamedTempFile::new().unwrap();
+    for message in messages {
+        file.write_all(message).unwrap();
+    }
+    file.flush().unwrap();
+    file
+}
@@ -2,21 +2,41 @@
 //
 // This file handles terminal events (keyboard, resize, etc.)
 
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
+use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
 
-use crossterm::event::{self, Event as CrosstermEvent, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{self, Event as CrosstermEvent, KeyEvent, MouseEvent};
 
-/// Terminal events
-#[derive(Debug, Clone, Copy)]
+use crate::parser::{DltFile, Index, Result as ParserResult};
+
+/// Terminal and background events
 pub enum Event {
     /// Key press
     Key(KeyEvent),
+    /// Mouse click or scroll
+    Mouse(MouseEvent),
     /// Terminal resize
     Resize(u16, u16),
     /// Tick event for animations
     Tick,
+    /// Progress (0.0 to 1.0) of a file loading in the background
+    LoadProgress(PathBuf, f64),
+    /// A file finished loading in the background
+    FileLoaded(PathBuf, ParserResult<(Arc<DltFile>, Arc<Index>)>),
+    /// Matches found by a background search in its next chunk of messages,
+    /// with the progress (0.0 to 1.0) of the search
+    SearchProgress(u64, Vec<usize>, f64),
+    /// A background search finished
+    SearchFinished(u64),
+    /// Number of messages written so far by a background export
+    ExportProgress(u64, usize),
+    /// A background export finished, with the number of messages written
+    ExportFinished(u64, io::Result<usize>),
 }
 
 /// Event handler
@@ -27,17 +47,22 @@ pub struct EventHandler {
     receiver: mpsc::Receiver<Event>,
     /// Event handler thread
     handler: Option<thread::JoinHandle<()>>,
+    /// Flag telling the event handler thread to stop
+    stop: Arc<AtomicBool>,
 }
 
 impl EventHandler {
     /// Create a new event handler with the given tick rate
     pub fn new(tick_rate: Duration) -> Self {
         let (sender, receiver) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
         let handler = {
             let sender = sender.clone();
+            let stop = stop.clone();
             thread::spawn(move || {
                 let mut last_tick = Instant::now();
-                loop {
+                // Polling times out at least once per tick, so the stop flag is seen promptly
+                while !stop.load(Ordering::Relaxed) {
                     let timeout = tick_rate
                         .checked_sub(last_tick.elapsed())
                         .unwrap_or(Duration::from_secs(0));
@@ -45,14 +70,13 @@ impl EventHandler {
                     if event::poll(timeout).expect("Failed to poll for events") {
                         match event::read().expect("Failed to read event") {
                             CrosstermEvent::Key(key) => {
-                                if key.code == KeyCode::Char('c')
-                                    && key.modifiers == KeyModifiers::CONTROL
-                                {
-                                    // Exit on Ctrl+C
-                                    break;
-                                }
                                 sender.send(Event::Key(key)).expect("Failed to send event");
                             }
+                            CrosstermEvent::Mouse(mouse) => {
+                                sender
+                                    .send(Event::Mouse(mouse))
+                                    .expect("Failed to send event");
+                            }
                             CrosstermEvent::Resize(width, height) => {
                                 sender
                                     .send(Event::Resize(width, height))
@@ -74,9 +98,15 @@ impl EventHandler {
             sender,
             receiver,
             handler: Some(handler),
+            stop,
         }
     }
 
+    /// Get a sender for posting events from background threads
+    pub fn sender(&self) -> mpsc::Sender<Event> {
+        self.sender.clone()
+    }
+
     /// Receive the next event
     pub fn next(&self) -> Result<Event, mpsc::RecvError> {
         self.receiver.recv()
@@ -85,6 +115,7 @@ impl EventHandler {
 
 impl Drop for EventHandler {
     fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
         if let Some(handler) = self.handler.take() {
             handler.join().expect("Failed to join event handler thread");
         }
@@ -3,31 +3,66 @@
 // This module handles the user interface using the ratatui library.
 
 mod event;
+mod terminal;
 mod theme;
 mod views;
 
 pub use event::{Event, EventHandler};
+pub use terminal::TerminalGuard;
 pub use theme::Theme;
 pub use views::*;
 
+use std::rc::Rc;
+
 use crate::app::{App, ViewMode};
 use ratatui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
+    widgets::{Block, Borders},
     Frame,
 };
 
-/// Render the UI
-pub fn render(f: &mut Frame, app: &App) {
-    // Create the layout
-    let chunks = Layout::default()
+/// Split the screen into status bar, main content and command line
+fn main_layout(area: Rect) -> Rc<[Rect]> {
+    Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(1), // Status bar
             Constraint::Min(0),    // Main content
             Constraint::Length(1), // Command line
         ])
-        .split(f.size());
+        .split(area)
+}
+
+/// Split the list view into file browser and log list
+///
+/// The file browser gets no width when it is hidden, which it is by default
+/// while only one file is open.
+fn list_view_layout(app: &App, area: Rect) -> Rc<[Rect]> {
+    let ratio = if app.is_browser_visible() {
+        app.browser_ratio
+    } else {
+        0
+    };
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(ratio),       // File browser
+            Constraint::Percentage(100 - ratio), // Log list
+        ])
+        .split(area)
+}
+
+/// Get the area of the log list rows (inside its border) in the list view
+pub fn log_list_area(app: &App, size: Rect) -> Rect {
+    let list = list_view_layout(app, main_layout(size)[1])[1];
+    Block::default().borders(Borders::ALL).inner(list)
+}
+
+/// Render the UI
+pub fn render(f: &mut Frame, app: &App) {
+    // Create the layout
+    let chunks = main_layout(f.size());
 
     // Render the status bar
     views::status_bar::render(f, app, chunks[0]);
@@ -37,6 +72,8 @@ pub fn render(f: &mut Frame, app: &App) {
         ViewMode::List => render_list_view(f, app, chunks[1]),
         ViewMode::Detail => views::detail_view::render(f, app, chunks[1]),
         ViewMode::Help => views::help::render(f, app, chunks[1]),
+        ViewMode::Picker => views::file_browser::render_picker(f, app, chunks[1]),
+        ViewMode::Histogram => views::histogram::render(f, app, chunks[1]),
     }
 
     // Render the command line
@@ -46,16 +83,12 @@ pub fn render(f: &mut Frame, app: &App) {
 /// Render the list view
 fn render_list_view(f: &mut Frame, app: &App, area: Rect) {
     // Split the area into file browser and log list
-    let chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(20), // File browser
-            Constraint::Percentage(80), // Log list
-        ])
-        .split(area);
+    let chunks = list_view_layout(app, area);
 
     // Render the file browser
-    views::file_browser::render(f, app, chunks[0]);
+    if app.is_browser_visible() {
+        views::file_browser::render(f, app, chunks[0]);
+    }
 
     // Render the log list
     views::log_list::render(f, app, chunks[1]);
@@ -0,0 +1,105 @@
+// Terminal Setup
+//
+// This file sets up the terminal for the TUI and restores it when the
+// application exits, including when it panics.
+
+use std::io::{self, Stdout};
+use std::ops::{Deref, DerefMut};
+use std::panic;
+
+use crossterm::{
+    cursor::Show,
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{backend::CrosstermBackend, Terminal};
+
+/// Terminal in raw mode on the alternate screen, restored when dropped
+///
+/// On a panic the terminal is restored twice: first by the panic hook, so the
+/// panic message is printed to the normal screen, then by `Drop` while the
+/// stack unwinds. Restoring is idempotent, leaving raw mode and the alternate
+/// screen again has no effect. With `panic = "abort"` only the hook runs, and
+/// on normal exits and returned errors only `Drop` does.
+pub struct TerminalGuard {
+    /// The ratatui terminal
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+    /// Whether mouse events are captured
+    mouse: bool,
+}
+
+impl TerminalGuard {
+    /// Enter raw mode and the alternate screen, capturing mouse events if `mouse`
+    /// is set (which leaves text selection to the application)
+    ///
+    /// Also installs a panic hook that restores the terminal before the panic
+    /// message is printed, so it isn't lost on the alternate screen.
+    pub fn new(mouse: bool) -> io::Result<Self> {
+        install_panic_hook(mouse);
+
+        let setup = || {
+            enable_raw_mode()?;
+            let mut stdout = io::stdout();
+            execute!(stdout, EnterAlternateScreen)?;
+            if mouse {
+                execute!(stdout, EnableMouseCapture)?;
+            }
+            Terminal::new(CrosstermBackend::new(stdout))
+        };
+
+        match setup() {
+            Ok(terminal) => Ok(Self { terminal, mouse }),
+            Err(e) => {
+                // Undo whatever part of the setup succeeded
+                let _ = restore(mouse);
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Deref for TerminalGuard {
+    type Target = Terminal<CrosstermBackend<Stdout>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.terminal
+    }
+}
+
+impl DerefMut for TerminalGuard {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.terminal
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        if let Err(e) = restore(self.mouse) {
+            eprintln!("Failed to restore terminal: {}", e);
+        }
+    }
+}
+
+/// Leave raw mode and the alternate screen, stop capturing mouse events if
+/// `mouse` is set, and show the cursor again
+fn restore(mouse: bool) -> io::Result<()> {
+    disable_raw_mode()?;
+    let mut stdout = io::stdout();
+    if mouse {
+        execute!(stdout, DisableMouseCapture)?;
+    }
+    execute!(stdout, LeaveAlternateScreen, Show)
+}
+
+/// Restore the terminal before the default panic hook prints the panic
+///
+/// The hook wraps the one installed before and stays installed after the guard
+/// is dropped; restoring a terminal that is already restored is harmless.
+fn install_panic_hook(mouse: bool) {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = restore(mouse);
+        default_hook(info);
+    }));
+}
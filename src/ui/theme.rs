@@ -6,6 +6,7 @@ use crate::parser::LogLevel;
 use ratatui::style::{Color, Style};
 
 /// UI Theme
+#[derive(Debug, Clone)]
 pub struct Theme {
     /// Background color
     pub background: Color,
@@ -17,6 +18,8 @@ pub struct Theme {
     pub selected_bg: Color,
     /// Selected item foreground color
     pub selected_fg: Color,
+    /// Marked range background color
+    pub range_bg: Color,
     /// Status bar background color
     pub status_bar_bg: Color,
     /// Status bar foreground color
@@ -51,6 +54,7 @@ impl Default for Theme {
             highlight: Color::Yellow,
             selected_bg: Color::DarkGray,
             selected_fg: Color::White,
+            range_bg: Color::Indexed(238),
             status_bar_bg: Color::Blue,
             status_bar_fg: Color::White,
             command_line_bg: Color::DarkGray,
@@ -68,6 +72,42 @@ impl Default for Theme {
 }
 
 impl Theme {
+    /// Names of the built-in themes
+    pub const NAMES: [&'static str; 2] = ["default", "light"];
+
+    /// Get a built-in theme by name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "default" | "dark" => Some(Self::default()),
+            "light" => Some(Self::light()),
+            _ => None,
+        }
+    }
+
+    /// Theme for terminals with a light background
+    pub fn light() -> Self {
+        Self {
+            background: Color::White,
+            foreground: Color::Black,
+            highlight: Color::Indexed(130),
+            selected_bg: Color::Indexed(153),
+            selected_fg: Color::Black,
+            range_bg: Color::Indexed(254),
+            status_bar_bg: Color::Indexed(25),
+            status_bar_fg: Color::White,
+            command_line_bg: Color::Indexed(252),
+            command_line_fg: Color::Black,
+            error: Color::Red,
+            warning: Color::Indexed(130),
+            info: Color::Indexed(28),
+            debug: Color::Indexed(30),
+            verbose: Color::DarkGray,
+            fatal: Color::Magenta,
+            border: Color::DarkGray,
+            title: Color::Blue,
+        }
+    }
+
     /// Get the style for a log level
     pub fn style_for_log_level(&self, level: Option<LogLevel>) -> Style {
         match level {
@@ -86,6 +126,11 @@ impl Theme {
         Style::default().bg(self.selected_bg).fg(self.selected_fg)
     }
 
+    /// Get the style for rows in a marked range
+    pub fn range_style(&self) -> Style {
+        Style::default().bg(self.range_bg)
+    }
+
     /// Get the style for the status bar
     pub fn status_bar_style(&self) -> Style {
         Style::default()
@@ -3,20 +3,61 @@
 // This file implements the command line view at the bottom of the application.
 
 use crate::app::App;
-use crate::app::InputMode;
-use crate::ui::Theme;
+use crate::app::{key_name, Action, InputMode};
 use ratatui::{
     backend::Backend,
     layout::Rect,
     style::Style,
     text::{Line, Span, Text},
-    widgets::Paragraph,
+    widgets::{Gauge, Paragraph},
     Frame,
 };
 
+/// Actions hinted at in normal mode, with their labels
+const KEY_HINTS: [(Action, &str); 7] = [
+    (Action::Quit, "quit"),
+    (Action::SearchMode, "search"),
+    (Action::FilterMode, "filter"),
+    (Action::ShowHelp, "help"),
+    (Action::NextMatch, "next"),
+    (Action::PrevMatch, "prev"),
+    (Action::ToggleCase, "case"),
+];
+
 /// Render the command line
 pub fn render(f: &mut Frame, app: &App, area: Rect) {
-    let theme = Theme::default();
+    let theme = &app.theme;
+
+    // Show the loading progress instead of the key hints while files are loading
+    if app.input_mode == InputMode::Normal && app.indexing_progress < 1.0 {
+        let gauge = Gauge::default()
+            .gauge_style(Style::default().fg(theme.highlight))
+            .ratio(app.indexing_progress.clamp(0.0, 1.0))
+            .label(format!(
+                "Loading {} file(s)… {:.0}%",
+                app.loading.len(),
+                app.indexing_progress * 100.0
+            ));
+        f.render_widget(gauge, area);
+        return;
+    }
+
+    // Likewise while a search is running
+    if let Some(progress) = app
+        .search_progress()
+        .filter(|_| app.input_mode == InputMode::Normal)
+    {
+        let gauge = Gauge::default()
+            .gauge_style(Style::default().fg(theme.highlight))
+            .ratio(progress.clamp(0.0, 1.0))
+            .label(format!(
+                "Searching… {:.0}% ({} matches, Esc to cancel)",
+                progress * 100.0,
+                app.search_results.len()
+            ));
+        f.render_widget(gauge, area);
+        return;
+    }
 
     // Create the command line text
     let line = match app.input_mode {
@@ -35,6 +76,13 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
                 Span::raw(&app.command_input),
             ])
         }
+        InputMode::Command => {
+            // Show command input
+            Line::from(vec![
+                Span::styled(":", Style::default().fg(theme.highlight)),
+                Span::raw(&app.command_input),
+            ])
+        }
         InputMode::Normal => {
             if !app.command_input.is_empty() {
                 // Show the command being typed
@@ -43,23 +91,22 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
                     Span::raw(&app.command_input),
                 ])
             } else {
-                // Show help text
-                Line::from(vec![
-                    Span::styled("q", Style::default().fg(theme.highlight)),
-                    Span::raw(":quit "),
-                    Span::styled("/", Style::default().fg(theme.highlight)),
-                    Span::raw(":search "),
-                    Span::styled("f", Style::default().fg(theme.highlight)),
-                    Span::raw(":filter "),
-                    Span::styled("h", Style::default().fg(theme.highlight)),
-                    Span::raw(":help "),
-                    Span::styled("n", Style::default().fg(theme.highlight)),
-                    Span::raw(":next "),
-                    Span::styled("N", Style::default().fg(theme.highlight)),
-                    Span::raw(":prev "),
-                    Span::styled("i", Style::default().fg(theme.highlight)),
-                    Span::raw(":case"),
-                ])
+                // Show key hints, using the first key bound to each action
+                let mut spans = Vec::new();
+                for (action, label) in KEY_HINTS {
+                    let Some(&key) = app.keymap.keys_for(action).first() else {
+                        continue;
+                    };
+                    if !spans.is_empty() {
+                        spans.push(Span::raw(" "));
+                    }
+                    spans.push(Span::styled(
+                        key_name(key),
+                        Style::default().fg(theme.highlight),
+                    ));
+                    spans.push(Span::raw(format!(":{}", label)));
+                }
+                Line::from(spans)
             }
         }
     };
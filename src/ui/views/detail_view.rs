@@ -3,50 +3,97 @@
 // This file implements the detail view that shows the details of a selected DLT message.
 
 use crate::app::App;
-use crate::parser::DltMessage;
+use crate::parser::{
+    cut_display_text, hex_dump, strip_ansi, Argument, DltMessage, JsonDecoder, PayloadDecoder,
+};
 use crate::ui::Theme;
 use ratatui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
     style::Style,
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, Borders, Paragraph, Row, Table, Wrap},
     Frame,
 };
 
 /// Render the detail view
 pub fn render(f: &mut Frame, app: &App, area: Rect) {
-    let theme = Theme::default();
+    let theme = &app.theme;
 
-    // Create the block
+    // Create the block, showing the position of the message in the filtered list
+    let title = if app.filtered_messages.is_empty() {
+        "Message Details".to_string()
+    } else {
+        format!(
+            "Message Details ({}/{})",
+            app.selected_message_idx + 1,
+            app.filtered_messages.len()
+        )
+    };
     let block = Block::default()
-        .title("Message Details")
+        .title(title)
         .borders(Borders::ALL)
         .border_style(theme.border_style())
         .title_style(theme.title_style());
 
-    // Split the area into header and payload
+    // Get the selected message, and its header bytes if they are shown
+    let msg = app.selected_message();
+    let raw_header = msg
+        .as_ref()
+        .filter(|_| app.show_raw_header)
+        .and_then(|msg| {
+            let bytes = app.selected_message_bytes()?;
+            bytes.get(..msg.header_len())
+        });
+    let raw_header_fields = match (&msg, raw_header) {
+        (Some(msg), Some(bytes)) => header_fields(msg, bytes.len()),
+        _ => Vec::new(),
+    };
+
+    // Split the area into header, raw header (if shown) and payload
+    let raw_header_height = if raw_header.is_some() {
+        raw_header_fields.len() as u16 + 2
+    } else {
+        0
+    };
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(8), // Header
-            Constraint::Min(0),    // Payload
+            Constraint::Length(9),                 // Header
+            Constraint::Length(raw_header_height), // Raw header
+            Constraint::Min(0),                    // Payload
         ])
         .split(block.inner(area));
 
     // Render the block
     f.render_widget(block, area);
 
-    // Get the selected message
-    if let Some(msg) = app.selected_message() {
+    if let Some(msg) = msg {
         // Render the header
-        render_header(f, &msg, chunks[0], &theme);
+        render_header(f, app, &msg, chunks[0], theme);
+        if let Some(bytes) = raw_header {
+            render_raw_header(f, bytes, &raw_header_fields, chunks[1], theme);
+        }
 
-        // Render the payload
-        render_payload(f, &msg, chunks[1], &theme);
+        // Render the payload, with a hex dump below it if enabled
+        if app.show_hex {
+            let payload_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(chunks[2]);
+
+            render_payload(f, app, &msg, payload_chunks[0], theme);
+            render_hex(f, app, &msg, payload_chunks[1], theme);
+        } else {
+            render_payload(f, app, &msg, chunks[2], theme);
+        }
     } else {
-        // No message selected
-        let text = Text::from("No message selected");
+        // No message selected, or none shown at all
+        let text = Text::from(if app.filtered_messages.is_empty() {
+            app.no_messages_text()
+        } else {
+            "No message selected"
+        });
         let paragraph = Paragraph::new(text)
             .style(Style::default().fg(theme.foreground))
             .wrap(Wrap { trim: true });
@@ -56,14 +103,16 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
 }
 
 /// Render the message header
-fn render_header(f: &mut Frame, msg: &DltMessage, area: Rect, theme: &Theme) {
+fn render_header(f: &mut Frame, app: &App, msg: &DltMessage, area: Rect, theme: &Theme) {
     let mut lines = Vec::new();
 
     // Timestamp
-    let timestamp = msg.timestamp().format("%Y-%m-%d %H:%M:%S%.6f");
+    let timestamp = app
+        .timezone
+        .format(msg.timestamp(), &app.settings.detail_timestamp_format);
     lines.push(Line::from(vec![
         Span::styled("Timestamp: ", theme.title_style()),
-        Span::raw(format!("{}", timestamp)),
+        Span::raw(timestamp),
     ]));
 
     // ECU ID
@@ -99,17 +148,48 @@ fn render_header(f: &mut Frame, msg: &DltMessage, area: Rect, theme: &Theme) {
         ]));
     }
 
-    // Message type
-    lines.push(Line::from(vec![
+    // Message type, with a warning if the header declares a different number of
+    // arguments than the payload has
+    let mut message_type = vec![
         Span::styled("Message Type: ", theme.title_style()),
-        Span::raw(format!("{:?}", msg.message_type())),
-    ]));
+        Span::raw(format!(
+            "{:?}{}",
+            msg.message_type(),
+            if msg.is_verbose() { " (verbose)" } else { "" }
+        )),
+    ];
+    if let Some((declared, found)) = msg.argument_count_mismatch() {
+        message_type.push(Span::styled(
+            format!(" [ARGS: {} declared, {} found]", declared, found),
+            Style::default().fg(theme.warning),
+        ));
+    }
+    lines.push(Line::from(message_type));
 
     // Message counter
-    lines.push(Line::from(vec![
+    let mut counter = vec![
         Span::styled("Message Counter: ", theme.title_style()),
         Span::raw(format!("{}", msg.standard_header.message_counter)),
-    ]));
+    ];
+    if let Some(missing) = app.counter_gap(app.selected_message_idx) {
+        counter.push(Span::styled(
+            format!(" ({} missing before)", missing),
+            Style::default().fg(theme.warning),
+        ));
+    }
+    lines.push(Line::from(counter));
+
+    // Position of the message in its file, starting with the storage header
+    if let (Some(offset), Some(bytes)) =
+        (app.selected_message_offset(), app.selected_message_bytes())
+    {
+        lines.push(Line::from(vec![
+            Span::styled("Offset: ", theme.title_style()),
+            Span::raw(format!("0x{:x} ({})", offset, offset)),
+            Span::styled("  Length: ", theme.title_style()),
+            Span::raw(format!("{} bytes", bytes.len())),
+        ]));
+    }
 
     // Render the paragraph
     let text = Text::from(lines);
@@ -120,8 +200,87 @@ fn render_header(f: &mut Frame, msg: &DltMessage, area: Rect, theme: &Theme) {
     f.render_widget(paragraph, area);
 }
 
+/// Get the header fields of a message as (name, length in bytes), in file order
+///
+/// Bytes after the known fields, up to `header_len`, are listed as "other".
+fn header_fields(msg: &DltMessage, header_len: usize) -> Vec<(&'static str, usize)> {
+    let mut fields = if msg.storage_header.is_some() {
+        vec![
+            ("Storage pattern", 4),
+            ("Seconds", 4),
+            ("Microseconds", 4),
+            ("ECU ID", 4),
+        ]
+    } else {
+        vec![("Serial pattern", 4)]
+    };
+    fields.extend([("Header type", 1), ("Counter", 1), ("Length", 2)]);
+    if msg.extended_header.is_some() {
+        fields.extend([
+            ("Message info", 1),
+            ("Arguments", 1),
+            ("App ID", 4),
+            ("Context ID", 4),
+        ]);
+    }
+
+    let known: usize = fields.iter().map(|(_, len)| len).sum();
+    if header_len > known {
+        fields.push(("Other", header_len - known));
+    }
+    fields
+}
+
+/// Render the raw header bytes as a hex dump, one line per field
+fn render_raw_header(
+    f: &mut Frame,
+    bytes: &[u8],
+    fields: &[(&'static str, usize)],
+    area: Rect,
+    theme: &Theme,
+) {
+    // Create the block
+    let block = Block::default()
+        .title(format!("Raw Header ({} bytes)", bytes.len()))
+        .borders(Borders::ALL)
+        .border_style(theme.border_style())
+        .title_style(theme.title_style());
+
+    // Create a line per field with its offset, bytes and name
+    let mut offset = 0;
+    let lines: Vec<Line> = fields
+        .iter()
+        .map(|&(name, len)| {
+            let end = (offset + len).min(bytes.len());
+            let hex: Vec<String> = bytes[offset.min(end)..end]
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect();
+            let line = Line::from(vec![
+                Span::raw(format!("{:04x}  {:<12} ", offset, hex.join(" "))),
+                Span::styled(name, theme.title_style()),
+            ]);
+            offset += len;
+            line
+        })
+        .collect();
+
+    // Create the paragraph
+    let paragraph = Paragraph::new(Text::from(lines))
+        .style(Style::default().fg(theme.foreground))
+        .block(block);
+
+    f.render_widget(paragraph, area);
+}
+
 /// Render the message payload
-fn render_payload(f: &mut Frame, msg: &DltMessage, area: Rect, theme: &Theme) {
+fn render_payload(f: &mut Frame, app: &App, msg: &DltMessage, area: Rect, theme: &Theme) {
+    // Show verbose messages as a table of arguments when they can be decoded
+    if let Some(arguments) = msg.arguments() {
+        render_arguments(f, app, &arguments, area, theme);
+        return;
+    }
+
     // Create the block
     let block = Block::default()
         .title("Payload")
@@ -129,8 +288,24 @@ fn render_payload(f: &mut Frame, msg: &DltMessage, area: Rect, theme: &Theme) {
         .border_style(theme.border_style())
         .title_style(theme.title_style());
 
-    // Get the payload text
-    let payload_text = msg.payload_as_text();
+    // Get JSON payloads pretty-printed and colored if enabled, or the text from the
+    // first decoder that accepts the payload, or the text (or hex dump) of the
+    // payload, cut at the display limit and without ANSI escape sequences if enabled
+    let max_len = app.settings.max_payload_display;
+    let json = app.pretty_json.then(|| JsonDecoder.decode(msg)).flatten();
+    let payload_text = if let Some(json) = json {
+        highlight_json(&cut_display_text(&json, max_len), theme)
+    } else {
+        let text = match app.decoders.decode(msg) {
+            Some(text) => cut_display_text(&text, max_len).into_owned(),
+            None => msg.payload_display_text(max_len).into_owned(),
+        };
+        if app.settings.strip_ansi {
+            Text::from(strip_ansi(&text).into_owned())
+        } else {
+            Text::from(text)
+        }
+    };
 
     // Create the paragraph
     let paragraph = Paragraph::new(payload_text)
@@ -140,3 +315,119 @@ fn render_payload(f: &mut Frame, msg: &DltMessage, area: Rect, theme: &Theme) {
 
     f.render_widget(paragraph, area);
 }
+
+/// Color pretty-printed JSON: keys, strings, numbers and literals
+fn highlight_json(json: &str, theme: &Theme) -> Text<'static> {
+    let punctuation = Style::default().fg(theme.border);
+    let lines = json
+        .lines()
+        .map(|line| {
+            let mut spans = Vec::new();
+            let mut chars = line.char_indices().peekable();
+            while let Some((start, c)) = chars.next() {
+                let mut end = start + c.len_utf8();
+                let style = match c {
+                    '"' => {
+                        // Strings end at the next unescaped quote
+                        let mut escaped = false;
+                        for (i, c) in chars.by_ref() {
+                            end = i + c.len_utf8();
+                            if c == '"' && !escaped {
+                                break;
+                            }
+                            escaped = c == '\\' && !escaped;
+                        }
+                        // Keys are the strings followed by a colon
+                        if line[end..].trim_start().starts_with(':') {
+                            theme.title_style()
+                        } else {
+                            Style::default().fg(theme.info)
+                        }
+                    }
+                    '-' | '0'..='9' | 't' | 'f' | 'n' => {
+                        while let Some(&(i, c)) = chars.peek() {
+                            if !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+')) {
+                                break;
+                            }
+                            end = i + c.len_utf8();
+                            chars.next();
+                        }
+                        if c.is_ascii_alphabetic() {
+                            Style::default().fg(theme.debug)
+                        } else {
+                            Style::default().fg(theme.warning)
+                        }
+                    }
+                    c if c.is_whitespace() => Style::default().fg(theme.foreground),
+                    _ => punctuation,
+                };
+                spans.push(Span::styled(line[start..end].to_string(), style));
+            }
+            Line::from(spans)
+        })
+        .collect::<Vec<_>>();
+
+    Text::from(lines)
+}
+
+/// Render the decoded arguments of a verbose message as a table
+fn render_arguments(f: &mut Frame, app: &App, arguments: &[Argument], area: Rect, theme: &Theme) {
+    // Create the block
+    let block = Block::default()
+        .title(format!("Payload ({} arguments)", arguments.len()))
+        .borders(Borders::ALL)
+        .border_style(theme.border_style())
+        .title_style(theme.title_style());
+
+    // Create a row per argument, prefixing the value with its name if present
+    let rows: Vec<Row> = arguments
+        .iter()
+        .enumerate()
+        .map(|(i, arg)| {
+            let mut value = match &arg.name {
+                Some(name) => format!("{} = {}", name, arg.value),
+                None => arg.value.to_string(),
+            };
+            if app.settings.strip_ansi {
+                value = strip_ansi(&value).into_owned();
+            }
+            Row::new(vec![i.to_string(), arg.type_name(), value])
+        })
+        .collect();
+
+    // Create the table
+    let header = Row::new(vec!["#", "Type", "Value"]).style(theme.title_style());
+    let table = Table::new(rows)
+        .header(header)
+        .block(block)
+        .style(Style::default().fg(theme.foreground))
+        .widths(&[
+            Constraint::Length(4),
+            Constraint::Length(8),
+            Constraint::Percentage(100),
+        ]);
+
+    f.render_widget(table, area);
+}
+
+/// Render the payload as a hex dump
+fn render_hex(f: &mut Frame, app: &App, msg: &DltMessage, area: Rect, theme: &Theme) {
+    // Create the block
+    let block = Block::default()
+        .title(format!("Hex ({} bytes)", msg.payload.len()))
+        .borders(Borders::ALL)
+        .border_style(theme.border_style())
+        .title_style(theme.title_style());
+
+    // Create the paragraph, cutting the dump at the display limit
+    let max_len = app.settings.max_payload_display;
+    let mut dump = hex_dump(&msg.payload[..msg.payload.len().min(max_len)]);
+    if msg.payload.len() > max_len {
+        dump.push_str(&format!("\n… ({} more bytes)", msg.payload.len() - max_len));
+    }
+    let paragraph = Paragraph::new(dump)
+        .style(Style::default().fg(theme.foreground))
+        .block(block);
+
+    f.render_widget(paragraph, area);
+}
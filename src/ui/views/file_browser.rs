@@ -1,21 +1,21 @@
 // File Browser View
 //
-// This file implements the file browser view that shows the loaded DLT files.
+// This file implements the file browser view that shows the loaded DLT files,
+// and the file picker used to open new ones.
 
-use crate::app::App;
-use crate::ui::Theme;
+use crate::app::{App, PickerEntry};
 use ratatui::{
     backend::Backend,
     layout::Rect,
     style::Style,
     text::{Line, Span, Text},
-    widgets::{Block, Borders, List, ListItem},
+    widgets::{Block, Borders, List, ListItem, ListState},
     Frame,
 };
 
 /// Render the file browser
 pub fn render(f: &mut Frame, app: &App, area: Rect) {
-    let theme = Theme::default();
+    let theme = &app.theme;
 
     // Create the block
     let block = Block::default()
@@ -24,13 +24,12 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         .border_style(theme.border_style())
         .title_style(theme.title_style());
 
-    // Create the list items
+    // Create the list items, in the configured order
     let items: Vec<ListItem> = app
-        .files
+        .file_order
         .iter()
-        .enumerate()
-        .map(|(i, file)| {
-            let file_name = file
+        .map(|&i| {
+            let file_name = app.files[i]
                 .path()
                 .file_name()
                 .unwrap_or_default()
@@ -54,3 +53,52 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
 
     f.render_widget(list, area);
 }
+
+/// Render the file picker
+pub fn render_picker(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+
+    let Some(picker) = &app.picker else {
+        return;
+    };
+
+    // Create the block
+    let block = Block::default()
+        .title(format!("Open File - {}", picker.dir.display()))
+        .borders(Borders::ALL)
+        .border_style(theme.border_style())
+        .title_style(theme.title_style());
+
+    // Create the list items
+    let items: Vec<ListItem> = if picker.entries.is_empty() {
+        vec![ListItem::new("No DLT files found")]
+    } else {
+        picker
+            .entries
+            .iter()
+            .map(|entry| {
+                let style = match entry {
+                    PickerEntry::Recent(_) => theme.highlight_style(),
+                    PickerEntry::Parent | PickerEntry::Directory(_) => {
+                        Style::default().fg(theme.title)
+                    }
+                    PickerEntry::File(_) => Style::default(),
+                };
+
+                let line = Line::from(vec![Span::styled(entry.label(), style)]);
+                ListItem::new(Text::from(line))
+            })
+            .collect()
+    };
+
+    // Create the list state
+    let mut state = ListState::default();
+    state.select(Some(picker.selected));
+
+    // Create the list
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(theme.selected_style());
+
+    f.render_stateful_widget(list, area, &mut state);
+}
@@ -2,8 +2,7 @@
 //
 // This file implements the help view that shows keyboard shortcuts and commands.
 
-use crate::app::App;
-use crate::ui::Theme;
+use crate::app::{key_name, Action, App, Category};
 use ratatui::{
     backend::Backend,
     layout::Rect,
@@ -14,8 +13,8 @@ use ratatui::{
 };
 
 /// Render the help view
-pub fn render(f: &mut Frame, _app: &App, area: Rect) {
-    let theme = Theme::default();
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
 
     // Create the block
     let block = Block::default()
@@ -34,140 +33,176 @@ pub fn render(f: &mut Frame, _app: &App, area: Rect) {
     )]));
     lines.push(Line::from(vec![Span::raw("")]));
 
-    // Basic navigation
+    // Key bindings, grouped by category and taken from the active key map so
+    // remapped keys show up here
+    for category in Category::ALL {
+        lines.push(Line::from(vec![Span::styled(
+            category.title(),
+            theme.highlight_style(),
+        )]));
+        for action in Action::ALL
+            .into_iter()
+            .filter(|action| action.category() == category)
+        {
+            let keys: Vec<String> = app
+                .keymap
+                .keys_for(action)
+                .into_iter()
+                .map(key_name)
+                .collect();
+            if keys.is_empty() {
+                continue;
+            }
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:<16}", keys.join(", ")),
+                    theme.highlight_style(),
+                ),
+                Span::raw(action.description().to_string()),
+            ]));
+        }
+        lines.push(Line::from(vec![Span::raw("")]));
+    }
+
+    // Keys that aren't remappable
     lines.push(Line::from(vec![Span::styled(
-        "Navigation",
+        "Fixed Keys",
         theme.highlight_style(),
     )]));
-
-    // Add navigation key help lines
-    lines.push(Line::from(vec![
-        Span::styled(format!("  {:<14}", "j, ↓"), theme.highlight_style()),
-        Span::raw("Move down".to_string()),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled(format!("  {:<14}", "k, ↑"), theme.highlight_style()),
-        Span::raw("Move up".to_string()),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled(format!("  {:<14}", "g, Home"), theme.highlight_style()),
-        Span::raw("Go to top".to_string()),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled(format!("  {:<14}", "G, End"), theme.highlight_style()),
-        Span::raw("Go to bottom".to_string()),
-    ]));
     lines.push(Line::from(vec![
-        Span::styled(format!("  {:<14}", "PgUp, Ctrl+b"), theme.highlight_style()),
-        Span::raw("Page up".to_string()),
+        Span::styled(format!("  {:<16}", "Ctrl+c"), theme.highlight_style()),
+        Span::raw("Quit from any mode".to_string()),
     ]));
     lines.push(Line::from(vec![
-        Span::styled(format!("  {:<14}", "PgDn, Ctrl+f"), theme.highlight_style()),
-        Span::raw("Page down".to_string()),
+        Span::styled(format!("  {:<16}", "Esc"), theme.highlight_style()),
+        Span::raw("Cancel a running search or export".to_string()),
     ]));
     lines.push(Line::from(vec![
-        Span::styled(format!("  {:<14}", "Tab"), theme.highlight_style()),
-        Span::raw("Switch between panes".to_string()),
+        Span::styled(format!("  {:<16}", "↑/↓"), theme.highlight_style()),
+        Span::raw("Recall search/filter history while typing".to_string()),
     ]));
     lines.push(Line::from(vec![Span::raw("")]));
 
-    // View controls
+    // Filter commands
     lines.push(Line::from(vec![Span::styled(
-        "View Controls",
+        "Filter Commands",
         theme.highlight_style(),
     )]));
     lines.push(Line::from(vec![
-        Span::styled(format!("  {:<14}", "Enter"), theme.highlight_style()),
-        Span::raw("Toggle detail view".to_string()),
+        Span::styled(
+            format!("  {:<16}", ":filter app=APP"),
+            theme.highlight_style(),
+        ),
+        Span::raw("Filter by application ID".to_string()),
     ]));
     lines.push(Line::from(vec![
-        Span::styled(format!("  {:<14}", "h, ?"), theme.highlight_style()),
-        Span::raw("Show/hide help".to_string()),
+        Span::styled(
+            format!("  {:<16}", ":filter ctx=CTX"),
+            theme.highlight_style(),
+        ),
+        Span::raw("Filter by context ID".to_string()),
     ]));
     lines.push(Line::from(vec![
-        Span::styled(format!("  {:<14}", "n, p"), theme.highlight_style()),
-        Span::raw("Next/previous file".to_string()),
+        Span::styled(
+            format!("  {:<16}", ":filter level=LEVEL"),
+            theme.highlight_style(),
+        ),
+        Span::raw("Filter by log level (name or number)".to_string()),
     ]));
-    lines.push(Line::from(vec![Span::raw("")]));
-
-    // Filtering and searching
-    lines.push(Line::from(vec![Span::styled(
-        "Filtering and Searching",
-        theme.highlight_style(),
-    )]));
     lines.push(Line::from(vec![
-        Span::styled(format!("  {:<14}", "/"), theme.highlight_style()),
-        Span::raw("Search".to_string()),
+        Span::styled(
+            format!("  {:<16}", ":filter level>=LEVEL"),
+            theme.highlight_style(),
+        ),
+        Span::raw("Filter by minimum log level".to_string()),
     ]));
     lines.push(Line::from(vec![
-        Span::styled(format!("  {:<14}", "n"), theme.highlight_style()),
-        Span::raw("Next search result".to_string()),
+        Span::styled(
+            format!("  {:<16}", ":filter type=TYPE"),
+            theme.highlight_style(),
+        ),
+        Span::raw("Filter by message type (log/trace/network/control)".to_string()),
     ]));
     lines.push(Line::from(vec![
-        Span::styled(format!("  {:<14}", "N"), theme.highlight_style()),
-        Span::raw("Previous search result".to_string()),
+        Span::styled(
+            format!("  {:<16}", ":filter time=FROM..TO"),
+            theme.highlight_style(),
+        ),
+        Span::raw("Filter by time of day (either end optional)".to_string()),
     ]));
     lines.push(Line::from(vec![
-        Span::styled(format!("  {:<14}", "f"), theme.highlight_style()),
-        Span::raw("Filter mode".to_string()),
+        Span::styled(
+            format!("  {:<16}", ":filter clear"),
+            theme.highlight_style(),
+        ),
+        Span::raw("Clear all filters".to_string()),
     ]));
     lines.push(Line::from(vec![
-        Span::styled(format!("  {:<14}", "c"), theme.highlight_style()),
-        Span::raw("Clear filters".to_string()),
+        Span::styled(format!("  {:<16}", ":filter app="), theme.highlight_style()),
+        Span::raw("Clear one field (clauses combine: app=NET ctx=TCP)".to_string()),
     ]));
     lines.push(Line::from(vec![
-        Span::styled(format!("  {:<14}", "i"), theme.highlight_style()),
-        Span::raw("Toggle case sensitivity for search".to_string()),
+        Span::styled(format!("  {:<16}", ":ids nocase"), theme.highlight_style()),
+        Span::raw("Match IDs ignoring case (:ids case to undo)".to_string()),
     ]));
     lines.push(Line::from(vec![Span::raw("")]));
 
-    // Filter commands
+    // Other commands
     lines.push(Line::from(vec![Span::styled(
-        "Filter Commands",
+        "Other Commands",
         theme.highlight_style(),
     )]));
     lines.push(Line::from(vec![
         Span::styled(
-            format!("  {:<14}", ":filter app=APP"),
+            format!("  {:<16}", ":count PATTERN"),
             theme.highlight_style(),
         ),
-        Span::raw("Filter by application ID".to_string()),
+        Span::raw("Count matching messages without jumping".to_string()),
+    ]));
+    lines.push(Line::from(vec![
+        Span::styled(format!("  {:<16}", ":anchor"), theme.highlight_style()),
+        Span::raw("Show timestamps relative to the selected message".to_string()),
     ]));
     lines.push(Line::from(vec![
         Span::styled(
-            format!("  {:<14}", ":filter ctx=CTX"),
+            format!("  {:<16}", ":anchor clear"),
             theme.highlight_style(),
         ),
-        Span::raw("Filter by context ID".to_string()),
+        Span::raw("Measure relative timestamps from the first message".to_string()),
     ]));
     lines.push(Line::from(vec![
         Span::styled(
-            format!("  {:<14}", ":filter level=LEVEL"),
+            format!("  {:<16}", ":sortfiles ORDER"),
             theme.highlight_style(),
         ),
-        Span::raw("Filter by log level".to_string()),
+        Span::raw("Sort files by load, name, modified or messages".to_string()),
     ]));
     lines.push(Line::from(vec![
         Span::styled(
-            format!("  {:<14}", ":filter clear"),
+            format!("  {:<16}", ":hl PATTERN COLOR"),
             theme.highlight_style(),
         ),
-        Span::raw("Clear all filters".to_string()),
+        Span::raw("Highlight another pattern in a color (:unhl [PATTERN] removes)".to_string()),
+    ]));
+    lines.push(Line::from(vec![
+        Span::styled(format!("  {:<16}", ":reload"), theme.highlight_style()),
+        Span::raw("Re-open all files after they changed on disk".to_string()),
+    ]));
+    lines.push(Line::from(vec![
+        Span::styled(format!("  {:<16}", ":diagnostics"), theme.highlight_style()),
+        Span::raw("Show bytes and messages of the file that couldn't be read".to_string()),
+    ]));
+    lines.push(Line::from(vec![
+        Span::styled(format!("  {:<16}", ":context N"), theme.highlight_style()),
+        Span::raw("Show N unfiltered messages around the selected one (Esc returns)".to_string()),
     ]));
-    lines.push(Line::from(vec![Span::raw("")]));
-
-    // Other commands
-    lines.push(Line::from(vec![Span::styled(
-        "Other Commands",
-        theme.highlight_style(),
-    )]));
     lines.push(Line::from(vec![
-        Span::styled(format!("  {:<14}", "q, Ctrl+c"), theme.highlight_style()),
-        Span::raw("Quit".to_string()),
+        Span::styled(format!("  {:<16}", ":export PATH"), theme.highlight_style()),
+        Span::raw("Write the range or all shown messages (.json for JSON)".to_string()),
     ]));
     lines.push(Line::from(vec![
-        Span::styled(format!("  {:<14}", "r"), theme.highlight_style()),
-        Span::raw("Reload files".to_string()),
+        Span::styled(format!("  {:<16}", ":dump PATH"), theme.highlight_style()),
+        Span::raw("Write the raw payload to a file (--hex for a hex dump)".to_string()),
     ]));
 
     // Create the paragraph
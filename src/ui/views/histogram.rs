@@ -0,0 +1,103 @@
+// Histogram View
+//
+// This file implements the histogram view that shows the number of filtered
+// messages over time.
+
+use crate::app::App;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Bar, BarChart, BarGroup, Block, Borders, Paragraph},
+    Frame,
+};
+
+/// Timestamp format of the bin times
+const TIME_FORMAT: &str = "%H:%M:%S%.3f";
+
+/// Render the histogram
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+
+    let Some(histogram) = &app.histogram else {
+        return;
+    };
+
+    // Create the block
+    let block = Block::default()
+        .title("Messages over Time (←/→ select, Enter jump, Esc close)")
+        .borders(Borders::ALL)
+        .border_style(theme.border_style())
+        .title_style(theme.title_style());
+
+    // Split the area into the selected bin info, the chart and the time axis
+    let inner = block.inner(area);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Selected bin
+            Constraint::Min(0),    // Chart
+            Constraint::Length(1), // Time axis
+        ])
+        .split(inner);
+
+    // Render the block
+    f.render_widget(block, area);
+
+    // Show the period and count of the selected bin
+    let (start, end) = histogram.bin_range(histogram.selected);
+    let info = Line::from(vec![
+        Span::styled(
+            format!(
+                "{} – {}",
+                app.timezone.format(start, TIME_FORMAT),
+                app.timezone.format(end, TIME_FORMAT)
+            ),
+            theme.highlight_style(),
+        ),
+        Span::raw(format!(
+            ": {} messages",
+            histogram.counts[histogram.selected]
+        )),
+    ]);
+    f.render_widget(Paragraph::new(info), chunks[0]);
+
+    // Create a bar per bin, highlighting the selected one
+    let bars: Vec<Bar> = histogram
+        .counts
+        .iter()
+        .enumerate()
+        .map(|(bin, &count)| {
+            let style = if bin == histogram.selected {
+                theme.highlight_style()
+            } else {
+                Style::default().fg(theme.title)
+            };
+            Bar::default()
+                .value(count)
+                .text_value(String::new())
+                .style(style)
+        })
+        .collect();
+
+    // Spread the bars over the available width
+    let bar_width = (chunks[1].width / histogram.counts.len() as u16).max(1);
+    let chart = BarChart::default()
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(bar_width)
+        .bar_gap(0);
+    f.render_widget(chart, chunks[1]);
+
+    // Label the ends of the time axis
+    let (first, _) = histogram.bin_range(0);
+    let (_, last) = histogram.bin_range(histogram.counts.len() - 1);
+    let first = app.timezone.format(first, TIME_FORMAT);
+    let last = app.timezone.format(last, TIME_FORMAT);
+    let chart_width = (bar_width as usize * histogram.counts.len()).min(chunks[2].width as usize);
+    let padding = chart_width.saturating_sub(first.len() + last.len());
+    let axis = Line::from(format!("{}{}{}", first, " ".repeat(padding), last));
+    f.render_widget(
+        Paragraph::new(axis).style(Style::default().fg(theme.foreground)),
+        chunks[2],
+    );
+}
@@ -3,20 +3,28 @@
 // This file implements the log list view that shows the DLT messages.
 
 use crate::app::App;
-use crate::parser::DltMessage;
+use crate::parser::{DltMessage, MatchScope, MessageType};
 use crate::ui::Theme;
+use chrono::{DateTime, TimeDelta, Utc};
 use ratatui::{
     backend::Backend,
     layout::Rect,
-    style::Style,
+    style::{Color, Modifier, Style},
     text::{Line, Span, Text},
     widgets::{Block, Borders, List, ListItem, ListState},
     Frame,
 };
+use unicode_width::UnicodeWidthChar;
+
+/// Indicator appended to rows that are search results
+const MATCH_INDICATOR: &str = " [MATCH]";
+
+/// Indicator appended to rows whose message counter skipped values
+const GAP_INDICATOR: &str = " [GAP]";
 
 /// Render the log list
 pub fn render(f: &mut Frame, app: &App, area: Rect) {
-    let theme = Theme::default();
+    let theme = &app.theme;
 
     // Create the block
     let block = Block::default()
@@ -25,27 +33,37 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         .border_style(theme.border_style())
         .title_style(theme.title_style());
 
-    // Create the list items
-    let items: Vec<ListItem> = if app.files.is_empty() || app.filtered_messages.is_empty() {
-        vec![ListItem::new("No messages")]
+    // Width available to each row when payloads are truncated
+    let inner = block.inner(area);
+    let max_width = app.truncate_payload.then_some(inner.width as usize);
+
+    // Only the rows that fit in the area are built. Rows are mapped to positions
+    // in the filtered messages, which are shown newest first when reversed.
+    let height = inner.height as usize;
+    let offset = app.scroll_offset(height);
+    let end = (offset + height).min(app.filtered_messages.len());
+
+    // Reference point for relative timestamps
+    let anchor = if app.relative_timestamps {
+        app.anchor_time()
     } else {
-        let file = &app.files[app.current_file_idx];
+        None
+    };
 
-        app.filtered_messages
+    // Create the list items
+    let items: Vec<ListItem> = if app.files.is_empty() && !app.loading.is_empty() {
+        app.loading
             .iter()
-            .enumerate()
-            .map(|(i, &idx)| {
-                if let Ok(msg) = file.get_message(idx) {
-                    // Check if this message is in the search results
-                    let is_search_result = app.search_results.contains(&i);
-
-                    create_list_item(
-                        &msg,
-                        i == app.selected_message_idx,
-                        &theme,
-                        app.search_pattern.as_ref(),
-                        is_search_result,
-                    )
+            .map(|(path, _)| ListItem::new(format!("Loading {}…", path.display())))
+            .collect()
+    } else if app.files.is_empty() || app.filtered_messages.is_empty() {
+        vec![ListItem::new(app.no_messages_text())]
+    } else {
+        (offset..end)
+            .map(|row| app.display_index(row))
+            .map(|i| {
+                if let Some(msg) = app.message_at(i) {
+                    create_list_item(app, i, &msg, theme, max_width, anchor)
                 } else {
                     ListItem::new("Error loading message")
                 }
@@ -53,11 +71,15 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
             .collect()
     };
 
-    // Create the list state
+    // Create the list state, relative to the first visible row
     let mut state = ListState::default();
-    state.select(Some(app.selected_message_idx));
+    state.select(Some(
+        app.display_index(app.selected_message_idx)
+            .saturating_sub(offset),
+    ));
 
-    // Create the list
+    // Create the list; the highlight style is applied to the whole row, so the
+    // selection spans the full width even for short payloads
     let list = List::new(items)
         .block(block)
         .highlight_style(theme.selected_style());
@@ -65,16 +87,105 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
     f.render_stateful_widget(list, area, &mut state);
 }
 
-/// Create a list item for a DLT message
-fn create_list_item<'a>(
+/// Get a short label for a file, shown in the merged view
+fn file_label(app: &App, file_idx: usize) -> String {
+    app.files[file_idx]
+        .path()
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Format a time difference as `+S.mmm` (or `-S.mmm` before the anchor)
+pub fn format_relative_time(delta: TimeDelta) -> String {
+    let sign = if delta < TimeDelta::zero() { '-' } else { '+' };
+    let delta = delta.abs();
+    format!(
+        "{}{}.{:03}",
+        sign,
+        delta.num_seconds(),
+        delta.subsec_nanos() / 1_000_000
+    )
+}
+
+/// Check whether the payload of the selected message doesn't fit its row in a log
+/// list of the given inner width, so only the detail view shows all of it
+pub fn is_selected_row_cut(app: &App, width: usize) -> bool {
+    let i = app.selected_message_idx;
+    let Some(msg) = app.message_at(i) else {
+        return false;
+    };
+    let anchor = if app.relative_timestamps {
+        app.anchor_time()
+    } else {
+        None
+    };
+
+    let prefix = row_prefix(app, i, &msg, &app.theme, anchor);
+    let used = prefix.iter().map(|s| s.width()).sum::<usize>() + indicators_width(app, i);
+    let collapsed = row_payload(app, &msg, Some(width + 1));
+    truncate_to_width(&collapsed, width.saturating_sub(used)).1
+}
+
+/// Check whether the row of the message at a position in the filtered messages
+/// shows the search result indicator. The current result is always tagged, the
+/// others only if enabled in the settings.
+fn shows_match_indicator(app: &App, i: usize) -> bool {
+    app.current_search_result() == Some(i)
+        || (app.settings.show_match_indicator && app.is_search_result(i))
+}
+
+/// Get the width of the indicators shown after the payload of a row
+fn indicators_width(app: &App, i: usize) -> usize {
+    let mut width = 0;
+    if shows_match_indicator(app, i) {
+        width += MATCH_INDICATOR.len();
+    }
+    if app.counter_gap(i).is_some() {
+        width += GAP_INDICATOR.len();
+    }
+    width
+}
+
+/// Get the width of the message index column, fitting the largest index of the
+/// shown files so the column doesn't change width while scrolling
+fn line_number_width(app: &App) -> usize {
+    let count = if app.merged {
+        app.files.iter().map(|file| file.message_count()).max()
+    } else {
+        app.files
+            .get(app.current_file_idx)
+            .map(|file| file.message_count())
+    };
+    count.unwrap_or(0).saturating_sub(1).max(1).ilog10() as usize + 1
+}
+
+/// Create the spans of a row before the payload: the index of the message in its
+/// file if enabled, the payload kind, the source file in the merged view, the
+/// timestamp, the IDs and the log level
+fn row_prefix<'a>(
+    app: &App,
+    i: usize,
     msg: &DltMessage,
-    _selected: bool,
     theme: &'a Theme,
-    search_pattern: Option<&regex::Regex>,
-    is_search_result: bool,
-) -> ListItem<'a> {
-    // Format the timestamp
-    let timestamp = msg.timestamp().format("%H:%M:%S%.3f");
+    anchor: Option<DateTime<Utc>>,
+) -> Vec<Span<'a>> {
+    // In the merged view, show which file the message came from
+    let source = if app.merged {
+        app.message_location(i)
+            .map(|(file_idx, _)| file_label(app, file_idx))
+    } else {
+        None
+    };
+
+    // Format the timestamp, relative to the anchor if one is given
+    let timestamp = match anchor {
+        Some(anchor) => format_relative_time(msg.timestamp() - anchor),
+        None => app
+            .timezone
+            .format(msg.timestamp(), &app.settings.list_timestamp_format),
+    };
 
     // Get the log level and style
     let log_level = msg.log_level();
@@ -84,67 +195,235 @@ fn create_list_item<'a>(
     let app_id = msg.app_id().unwrap_or_else(|| "".to_string());
     let ctx_id = msg.context_id().unwrap_or_else(|| "".to_string());
 
-    // Format the payload (first line only)
-    let payload = msg.payload_as_text();
-    let first_line = payload.lines().next().unwrap_or("").to_string();
-
-    // Create the spans for the prefix
-    let mut spans = vec![
+    // Create the spans, starting with the index of the message in its file
+    let mut spans = Vec::new();
+    if app.show_line_numbers {
+        if let Some((_, msg_idx)) = app.message_location(i) {
+            spans.push(Span::styled(
+                format!("{:>width$} ", msg_idx, width = line_number_width(app)),
+                Style::default().fg(theme.border),
+            ));
+        }
+    }
+    spans.push(Span::styled(
+        format!("{} ", payload_kind(msg)),
+        Style::default()
+            .fg(theme.border)
+            .add_modifier(Modifier::DIM),
+    ));
+    if let Some(source) = source {
+        spans.push(Span::styled(
+            format!("{:<12.12} ", source),
+            Style::default().fg(theme.debug),
+        ));
+    }
+    spans.extend([
         Span::raw(format!("{} ", timestamp)),
         Span::styled(
             format!("{:4} {:4} ", app_id, ctx_id),
             Style::default().fg(theme.title),
         ),
+        // Messages other than log messages have no level, show their type instead
         Span::styled(
-            format!("[{:?}] ", log_level.unwrap_or_default()),
+            match log_level {
+                Some(level) => format!("[{:?}] ", level),
+                None => format!("[{:?}] ", msg.message_type()),
+            },
             level_style,
         ),
-    ];
-
-    // Highlight search matches in the payload if applicable
-    if let Some(pattern) = search_pattern {
-        let mut last_match_end = 0;
-        let mut matches = pattern.find_iter(&first_line).peekable();
-
-        if matches.peek().is_some() {
-            // There are matches, add spans with highlighted matches
-            for m in pattern.find_iter(&first_line) {
-                // Add text before the match
-                if m.start() > last_match_end {
-                    spans.push(Span::raw(first_line[last_match_end..m.start()].to_string()));
-                }
+    ]);
+    spans
+}
 
-                // Add the highlighted match
-                spans.push(Span::styled(
-                    first_line[m.start()..m.end()].to_string(),
-                    Style::default().fg(theme.highlight),
-                ));
+/// Format the payload of a row on one line, taking at most `limit` characters
+fn row_payload(app: &App, msg: &DltMessage, limit: Option<usize>) -> String {
+    msg.payload_line(
+        app.settings.max_payload_display,
+        app.settings.strip_ansi,
+        limit,
+    )
+}
 
-                last_match_end = m.end();
-            }
+/// Create a list item for the DLT message at a position in the filtered messages
+fn create_list_item<'a>(
+    app: &App,
+    i: usize,
+    msg: &DltMessage,
+    theme: &'a Theme,
+    max_width: Option<usize>,
+    anchor: Option<DateTime<Utc>>,
+) -> ListItem<'a> {
+    // Check if this message is in the search results
+    let is_current_match = app.current_search_result() == Some(i);
+    let show_match = shows_match_indicator(app, i);
+    let counter_gap = app.counter_gap(i);
+    let search_pattern = app
+        .search_pattern
+        .as_ref()
+        .filter(|pattern| msg.matches_text(pattern, MatchScope::Payload));
+
+    // Format the payload on one line, up to the end of the visible part
+    let collapsed = row_payload(app, msg, max_width.map(|width| app.h_scroll + width + 1));
+
+    // Skip horizontally scrolled characters
+    let scroll_offset = collapsed
+        .char_indices()
+        .nth(app.h_scroll)
+        .map_or(collapsed.len(), |(offset, _)| offset);
+
+    // Create the spans for the prefix
+    let mut spans = row_prefix(app, i, msg, theme, anchor);
+
+    // Truncate the payload to the space left after the prefix and indicator
+    let visible = &collapsed[scroll_offset..];
+    let (visible, truncated) = match max_width {
+        Some(width) => {
+            let used = spans.iter().map(|s| s.width()).sum::<usize>() + indicators_width(app, i);
+            truncate_to_width(visible, width.saturating_sub(used))
+        }
+        None => (visible, false),
+    };
 
-            // Add any remaining text after the last match
-            if last_match_end < first_line.len() {
-                spans.push(Span::raw(first_line[last_match_end..].to_string()));
+    // Color the matches of the search pattern, then of the highlight patterns, where
+    // earlier patterns win on overlaps. Matches are found in the whole collapsed
+    // line, so those on later lines or partly scrolled out still show.
+    let patterns = search_pattern
+        .map(|pattern| (pattern, theme.highlight))
+        .into_iter()
+        .chain(
+            app.highlights
+                .iter()
+                .map(|(pattern, color)| (pattern, *color)),
+        );
+    let mut colors: Vec<Option<Color>> = vec![None; visible.len()];
+    for (pattern, color) in patterns {
+        for m in pattern.find_iter(&collapsed) {
+            // Clip the match to the visible part
+            let start = m.start().saturating_sub(scroll_offset).min(visible.len());
+            let end = m.end().saturating_sub(scroll_offset).min(visible.len());
+            for byte_color in &mut colors[start..end] {
+                byte_color.get_or_insert(color);
             }
-        } else {
-            // No matches, just add the raw text
-            spans.push(Span::raw(first_line));
         }
-    } else {
-        // No search pattern, just add the raw text
-        spans.push(Span::raw(first_line));
     }
 
-    // Add a search result indicator if this is a search result
-    if is_search_result {
+    // Add the payload in runs of the same color; runs start and end at match
+    // boundaries, which are character boundaries
+    let mut run_start = 0;
+    for i in 1..=visible.len() {
+        if i < visible.len() && colors[i] == colors[run_start] {
+            continue;
+        }
+        let text = visible[run_start..i].to_string();
+        spans.push(match colors[run_start] {
+            Some(color) => Span::styled(text, Style::default().fg(color)),
+            None => Span::raw(text),
+        });
+        run_start = i;
+    }
+    // Mark cut payloads with a subtle ellipsis, the detail view shows them in full
+    if truncated {
         spans.push(Span::styled(
-            " [MATCH]",
-            Style::default().fg(theme.highlight),
+            "…",
+            Style::default()
+                .fg(theme.border)
+                .add_modifier(Modifier::DIM),
+        ));
+    }
+
+    // Add a search result indicator, emphasizing the current result
+    if show_match {
+        let style = if is_current_match {
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::REVERSED | Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.highlight)
+        };
+        spans.push(Span::styled(MATCH_INDICATOR, style));
+    }
+
+    // Add a gap indicator if messages were dropped before this one
+    if counter_gap.is_some() {
+        spans.push(Span::styled(
+            GAP_INDICATOR,
+            Style::default().fg(theme.warning),
         ));
     }
 
     let line = Line::from(spans);
 
-    ListItem::new(Text::from(line))
+    // Mark the rows of the marked range
+    let item = ListItem::new(Text::from(line));
+    if app.marked_range().is_some_and(|range| range.contains(&i)) {
+        item.style(theme.range_style())
+    } else {
+        item
+    }
+}
+
+/// Get a glyph for the kind of payload: `C` for control messages, `T` for text
+/// and `B` for binary payloads
+fn payload_kind(msg: &DltMessage) -> char {
+    if msg.message_type() == MessageType::Control {
+        'C'
+    } else if msg.payload_text.is_some() {
+        'T'
+    } else {
+        'B'
+    }
+}
+
+/// Truncate text to a display width, leaving room for an ellipsis
+///
+/// Returns the part of the text that fits, and whether it was cut (and the
+/// ellipsis should be shown).
+fn truncate_to_width(text: &str, width: usize) -> (&str, bool) {
+    let total: usize = text.chars().map(|c| c.width().unwrap_or(0)).sum();
+    if total <= width {
+        return (text, false);
+    }
+
+    // Keep whole characters only, leaving room for the ellipsis
+    let mut used = 0;
+    let mut end = 0;
+    for (offset, c) in text.char_indices() {
+        let w = c.width().unwrap_or(0);
+        if used + w + 1 > width {
+            break;
+        }
+        used += w;
+        end = offset + c.len_utf8();
+    }
+    (&text[..end], width > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Settings;
+    use crate::testing;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn selection_spans_the_full_row() {
+        let tmp = testing::write_file(&[
+            testing::message(0, "APP1", "CTX1", 4, "short"),
+            testing::message(1, "APP1", "CTX1", 4, "short"),
+        ]);
+        let mut app = App::new(Settings::default());
+        app.load_file(tmp.path().to_path_buf()).unwrap();
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 6)).unwrap();
+        terminal.draw(|f| render(f, &app, f.size())).unwrap();
+
+        // Inside the border, the whole first row has the selection background,
+        // including the blank cells after the payload
+        let buffer = terminal.backend().buffer();
+        let selected_bg = app.theme.selected_bg;
+        for x in 1..99 {
+            assert_eq!(buffer.get(x, 1).bg, selected_bg, "column {}", x);
+            assert_ne!(buffer.get(x, 2).bg, selected_bg, "column {}", x);
+        }
+    }
 }
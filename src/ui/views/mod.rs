@@ -6,5 +6,6 @@ pub mod command_line;
 pub mod detail_view;
 pub mod file_browser;
 pub mod help;
+pub mod histogram;
 pub mod log_list;
 pub mod status_bar;
@@ -2,8 +2,10 @@
 //
 // This file implements the status bar view at the top of the application.
 
-use crate::app::App;
-use crate::ui::Theme;
+use crate::app::{App, ViewMode};
+use crate::ui::log_list::{format_relative_time, is_selected_row_cut};
+use crate::ui::log_list_area;
+use chrono::{DateTime, Utc};
 use ratatui::{
     backend::Backend,
     layout::Rect,
@@ -15,13 +17,28 @@ use ratatui::{
 
 /// Render the status bar
 pub fn render(f: &mut Frame, app: &App, area: Rect) {
-    let theme = Theme::default();
+    let theme = &app.theme;
 
     // Create the status text
     let mut status_parts = Vec::new();
 
     // Add the file info
-    if !app.files.is_empty() {
+    if app.merged {
+        let message_count: usize = app.files.iter().map(|f| f.message_count()).sum();
+        let filtered_count = app.filtered_messages.len();
+
+        status_parts.push(Span::styled(
+            format!(" Merged ({} files) ", app.files.len()),
+            theme.title_style(),
+        ));
+
+        status_parts.push(Span::raw(" | "));
+
+        status_parts.push(Span::raw(format!(
+            "Messages: {}/{} ",
+            filtered_count, message_count
+        )));
+    } else if !app.files.is_empty() {
         let file = &app.files[app.current_file_idx];
         let file_name = file
             .path()
@@ -44,11 +61,85 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         )));
     }
 
+    // Add the position of the selection among the filtered messages
+    if !app.filtered_messages.is_empty() {
+        let position = app.selected_message_idx + 1;
+        let count = app.filtered_messages.len();
+        status_parts.push(Span::raw(" | "));
+        status_parts.push(Span::raw(format!(
+            "{}/{} ({}%) ",
+            position,
+            count,
+            position * 100 / count
+        )));
+    }
+
+    // Point to the detail view when the payload of the selected row is cut
+    if app.view_mode == ViewMode::List {
+        let width = log_list_area(app, f.size()).width as usize;
+        if is_selected_row_cut(app, width) {
+            if let Some(msg) = app.selected_message() {
+                let size = match &msg.payload_text {
+                    Some(text) => format!("{} chars", text.chars().count()),
+                    None => format!("{} bytes", msg.payload.len()),
+                };
+                status_parts.push(Span::raw(" | "));
+                status_parts.push(Span::styled(
+                    format!("Payload {}, Enter for detail", size),
+                    Style::default().fg(theme.border),
+                ));
+            }
+        }
+    }
+
+    // Add the number of messages dropped according to the message counters
+    let (gaps, dropped) = app.counter_gap_totals();
+    if gaps > 0 {
+        status_parts.push(Span::raw(" | "));
+        status_parts.push(Span::styled(
+            format!("Gaps: {} ({} dropped) ", gaps, dropped),
+            Style::default().fg(theme.warning),
+        ));
+    }
+
+    // Add the message limit, if it cut a shown file short
+    if let Some(limit) = app.message_limit {
+        if app.files.iter().any(|file| file.is_limited()) {
+            status_parts.push(Span::raw(" | "));
+            status_parts.push(Span::styled(
+                format!("[LIMIT {}]", limit),
+                Style::default().fg(theme.warning),
+            ));
+        }
+    }
+
+    // Add the number of files still loading
+    if !app.loading.is_empty() {
+        status_parts.push(Span::raw(" | "));
+        status_parts.push(Span::styled(
+            format!("Loading {} file(s)…", app.loading.len()),
+            Style::default().fg(theme.info),
+        ));
+    }
+
+    // Add the progress of a running export
+    if let Some((written, total)) = app.export_progress() {
+        status_parts.push(Span::raw(" | "));
+        status_parts.push(Span::styled(
+            format!("Exporting… {}/{} (Esc to cancel)", written, total),
+            Style::default().fg(theme.info),
+        ));
+    }
+
     // Add filter info
     if app.filter.app_id.is_some()
         || app.filter.context_id.is_some()
         || app.filter.log_level.is_some()
+        || app.filter.min_log_level.is_some()
         || app.filter.message_type.is_some()
+        || app.filter.time_range.is_some()
+        || !app.filter.excluded_app_ids.is_empty()
+        || !app.filter.excluded_context_ids.is_empty()
     {
         status_parts.push(Span::raw(" | "));
         status_parts.push(Span::styled("Filtered", theme.highlight_style()));
@@ -64,6 +155,102 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         if let Some(level) = &app.filter.log_level {
             status_parts.push(Span::raw(format!(" Level:{:?}", level)));
         }
+
+        if let Some(level) = &app.filter.min_log_level {
+            status_parts.push(Span::raw(format!(" Level>={:?}", level)));
+        }
+
+        if let Some(message_type) = &app.filter.message_type {
+            status_parts.push(Span::raw(format!(" Type:{:?}", message_type)));
+        }
+
+        if let Some((start, end)) = &app.filter.time_range {
+            status_parts.push(Span::raw(format!(
+                " Time:{}..{}",
+                format_time_bound(app, start),
+                format_time_bound(app, end)
+            )));
+        }
+
+        if !app.filter.excluded_app_ids.is_empty() {
+            status_parts.push(Span::raw(format!(
+                " !App:{}",
+                app.filter.excluded_app_ids.join(",")
+            )));
+        }
+
+        if !app.filter.excluded_context_ids.is_empty() {
+            status_parts.push(Span::raw(format!(
+                " !Ctx:{}",
+                app.filter.excluded_context_ids.join(",")
+            )));
+        }
+
+        // The ID comparison mode only matters if IDs are filtered
+        let filters_ids = app.filter.app_id.is_some()
+            || app.filter.context_id.is_some()
+            || !app.filter.excluded_app_ids.is_empty()
+            || !app.filter.excluded_context_ids.is_empty();
+        if app.filter.case_insensitive_ids && filters_ids {
+            status_parts.push(Span::raw(" (IDs any case)"));
+        }
+    }
+
+    // Add shown context info
+    if let Some(radius) = app.context_radius() {
+        status_parts.push(Span::raw(" | "));
+        status_parts.push(Span::styled(
+            format!("[CONTEXT ±{}] (Esc to return)", radius),
+            Style::default().fg(theme.info),
+        ));
+    }
+
+    // Add marked range info
+    if let Some(range) = app.marked_range() {
+        status_parts.push(Span::raw(" | "));
+        status_parts.push(Span::styled(
+            format!("[RANGE {}]", range.end() - range.start() + 1),
+            Style::default().fg(theme.info),
+        ));
+    }
+
+    // Add the time from the measure mark to the selected message
+    if let Some(delta) = app.measured_time() {
+        status_parts.push(Span::raw(" | "));
+        status_parts.push(Span::styled(
+            format!("Δ {}s since mark", format_relative_time(delta)),
+            Style::default().fg(theme.info),
+        ));
+    }
+
+    // Add follow mode info
+    if app.follow_mode {
+        status_parts.push(Span::raw(" | "));
+        if app.following {
+            status_parts.push(Span::styled("[FOLLOW]", Style::default().fg(theme.info)));
+        } else {
+            status_parts.push(Span::styled("[PAUSED]", Style::default().fg(theme.warning)));
+        }
+    }
+
+    // Add reversed order info
+    if app.reversed {
+        status_parts.push(Span::raw(" | "));
+        status_parts.push(Span::styled(
+            "[NEWEST FIRST]",
+            Style::default().fg(theme.info),
+        ));
+    }
+
+    // Add relative timestamp info
+    if app.relative_timestamps {
+        status_parts.push(Span::raw(" | "));
+        let anchor = if app.time_anchor.is_some() {
+            "[REL: anchor]"
+        } else {
+            "[REL: first]"
+        };
+        status_parts.push(Span::styled(anchor, Style::default().fg(theme.info)));
     }
 
     // Add search info
@@ -99,3 +286,12 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
 
     f.render_widget(status, area);
 }
+
+/// Format one end of a time range filter, leaving open ends empty
+fn format_time_bound(app: &App, time: &DateTime<Utc>) -> String {
+    if *time == DateTime::<Utc>::MIN_UTC || *time == DateTime::<Utc>::MAX_UTC {
+        String::new()
+    } else {
+        app.timezone.format(*time, "%H:%M:%S")
+    }
+}